@@ -0,0 +1,32 @@
+//! Compares chunk sizes of `par_hmap_chunked` for cheap and expensive functions.
+//!
+//! Run with `cargo bench --features parallel`.
+
+use ha::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..5 {f()}
+    start.elapsed() / 5
+}
+
+fn run(name: &str, f: &Func<f64, f64>, len: usize) {
+    let data: Vec<f64> = (0..len).map(|i| i as f64).collect();
+    let seq = time(|| {let _: Vec<f64> = data.clone().hmap(f);});
+    println!("{} ({} elements), sequential: {:?}", name, len, seq);
+    for &chunk_size in &[1, 16, 256, 4096, 65536] {
+        let par = time(|| {let _: Vec<f64> = data.clone().par_hmap_chunked(f, chunk_size);});
+        println!("{} ({} elements), chunk size {}: {:?}", name, len, chunk_size, par);
+    }
+}
+
+fn main() {
+    let cheap: Func<f64, f64> = Arc::new(|t| t * 2.0 + 1.0);
+    let expensive: Func<f64, f64> = Arc::new(|t| {
+        (0..2000).fold(t, |acc, i| (acc + i as f64).sin())
+    });
+    run("cheap", &cheap, 1_000_000);
+    run("expensive", &expensive, 10_000);
+}
@@ -0,0 +1,478 @@
+#![deny(missing_docs)]
+
+//! # Derive macros for Ha
+//!
+//! These macros are re-exported by the `ha` crate with the `derive` feature.
+//!
+//! A derived struct is declared the same way as in the `ha` docs,
+//! with one type parameter and every field of the type `Fun<T, X>`:
+//!
+//! ```ignore
+//! #[derive(Clone, Ho, Call)]
+//! pub struct Point<T = ()> where f64: Ho<T> {
+//!     pub x: Fun<T, f64>,
+//!     pub y: Fun<T, f64>,
+//! }
+//! ```
+//!
+//! With `#[derive(Call)]`, calling `PointFunc<T>` calls the function of every field.
+//!
+//! A field marked `#[ho(skip)]` is not higher order: it keeps its declared type
+//! in `<Name>Func<T>` and is cloned through by `call`, so its type must implement `Clone`.
+//!
+//! With `#[derive(HoFields)]`, the `Fun<T, f64>` fields of `<Name>Func<T>` can be accessed by name.
+//!
+//! With `#[derive(HoBuilder)]`, `<Name>Func::from_fns` creates `<Name>Func<T>` from one closure per field.
+//!
+//! `#[derive(HAccumulate)]`, `#[derive(HDiff)]` and `#[derive(HDerivative)]`
+//! implement the traits field by field,
+//! for both the ordinary and the higher order form.
+//!
+//! With `#[derive(HoLeaf)]`, a type without type parameters can be used as a constant field.
+//!
+//! The parser is hand-written on top of `proc_macro`, so it only supports this shape.
+
+use proc_macro::{Delimiter, Spacing, TokenStream, TokenTree};
+
+/// Derives `Ho<Arg<T>>` and declares the type alias `<Name>Func<T>`.
+///
+/// Fields marked `#[ho(skip)]` keep their type and must implement `Clone`.
+#[proc_macro_derive(Ho, attributes(ho))]
+pub fn derive_ho(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    let bounds: Vec<String> = s.fields.iter().filter(|f| !f.skip)
+        .map(|f| format!("{}: ::ha::Ho<::ha::Arg<{}>>", f.ty, s.param))
+        .collect();
+    format!(
+        "#[doc = \"Function type of `{name}`.\"]
+        {vis} type {name}Func<{t}> = {name}<::ha::Arg<{t}>>;
+        impl<{t}: Clone> ::ha::Ho<::ha::Arg<{t}>> for {name} where {bounds} {{
+            type Fun = {name}Func<{t}>;
+        }}",
+        vis = s.vis, name = s.name, t = s.param, bounds = bounds.join(", ")
+    ).parse().unwrap()
+}
+
+/// Derives `Call<T>` by calling the function of every field.
+///
+/// This requires `Ho<Arg<T>>`, e.g. from `#[derive(Ho)]`.
+/// Fields marked `#[ho(skip)]` are cloned.
+#[proc_macro_derive(Call, attributes(ho))]
+pub fn derive_call(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    let t = &s.param;
+    let bounds: Vec<String> = s.fields.iter().filter(|f| !f.skip)
+        .map(|f| format!("{}: ::ha::Call<{}>", f.ty, t))
+        .collect();
+    let calls: Vec<String> = s.fields.iter()
+        .map(|f| {
+            let call = if f.skip {
+                format!("::core::clone::Clone::clone(&f.{})", f.name)
+            } else {
+                format!("<{} as ::ha::Call<{}>>::call(&f.{}, val)", f.ty, t, f.name)
+            };
+            if s.named {format!("{}: {}", f.name, call)} else {call}
+        })
+        .collect();
+    let body = if s.named {
+        format!("{{{}}}", calls.join(", "))
+    } else {
+        format!("({})", calls.join(", "))
+    };
+    format!(
+        "impl<{t}: Copy> ::ha::Call<{t}> for {name} where {bounds} {{
+            fn call(f: &<Self as ::ha::Ho<::ha::Arg<{t}>>>::Fun, val: {t}) -> Self {{
+                {name}::<()> {body}
+            }}
+        }}",
+        name = s.name, t = t, bounds = bounds.join(", "), body = body
+    ).parse().unwrap()
+}
+
+/// Derives field access by name on `<Name>Func<T>`.
+///
+/// Generates `field_names()` and `get_field(&self, name)`.
+/// Every field that is not marked `#[ho(skip)]` must have the type `Fun<T, f64>`.
+#[proc_macro_derive(HoFields, attributes(ho))]
+pub fn derive_ho_fields(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    let fields: Vec<&Field> = s.fields.iter().filter(|f| !f.skip).collect();
+    if let Some(f) = fields.iter().find(|f| f.ty != "f64") {
+        return compile_error(&format!(
+            "HoFields requires field `{}` of `{}` to have the type `Fun<{}, f64>`", f.name, s.name, s.param
+        ));
+    }
+    let names: Vec<String> = fields.iter().map(|f| format!("{:?}", f.name)).collect();
+    let arms: Vec<String> = fields.iter()
+        .map(|f| format!("{:?} => ::core::option::Option::Some(&self.{}),", f.name, f.name))
+        .collect();
+    format!(
+        "impl<{t}> {name}<::ha::Arg<{t}>> {{
+            #[doc = \"Returns the names of the fields.\"]
+            {vis} fn field_names() -> &'static [&'static str] {{
+                &[{names}]
+            }}
+
+            #[doc = \"Returns the function of the field `name`, or `None` if there is no such field.\"]
+            {vis} fn get_field(&self, name: &str) -> ::core::option::Option<&::ha::Func<{t}, f64>> {{
+                match name {{
+                    {arms}
+                    _ => ::core::option::Option::None,
+                }}
+            }}
+        }}",
+        vis = s.vis, name = s.name, t = s.param, names = names.join(", "), arms = arms.join("\n")
+    ).parse().unwrap()
+}
+
+/// Derives the constructor `<Name>Func::from_fns` from one closure per field.
+///
+/// Every field that is not marked `#[ho(skip)]` must have the type `Fun<T, X>`
+/// where `X` implements `HoLeaf`, so that the field is a `Func<T, X>`.
+/// Skipped fields are passed by value.
+#[proc_macro_derive(HoBuilder, attributes(ho))]
+pub fn derive_ho_builder(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    let t = &s.param;
+    let args: Vec<String> = s.fields.iter().enumerate()
+        .map(|(n, f)| if f.skip {
+            format!("f{}: {}", n, f.ty)
+        } else {
+            format!("f{}: impl Fn({}) -> {} + Send + Sync + 'static", n, t, f.ty)
+        })
+        .collect();
+    let bounds: Vec<String> = s.fields.iter().filter(|f| !f.skip)
+        .map(|f| format!("{x}: ::ha::Ho<::ha::Arg<{t}>, Fun = ::ha::Func<{t}, {x}>>", x = f.ty, t = t))
+        .collect();
+    let values: Vec<String> = s.fields.iter().enumerate()
+        .map(|(n, f)| {
+            let value = if f.skip {format!("f{}", n)} else {format!("::ha::func(f{})", n)};
+            if s.named {format!("{}: {}", f.name, value)} else {value}
+        })
+        .collect();
+    let body = if s.named {
+        format!("{{{}}}", values.join(", "))
+    } else {
+        format!("({})", values.join(", "))
+    };
+    format!(
+        "impl<{t}: 'static> {name}<::ha::Arg<{t}>> where {bounds} {{
+            #[doc = \"Creates `{name}Func<{t}>` from one closure per field.\"]
+            #[allow(clippy::too_many_arguments)]
+            {vis} fn from_fns({args}) -> Self {{
+                {name} {body}
+            }}
+        }}",
+        vis = s.vis, name = s.name, t = t, bounds = bounds.join(", "), args = args.join(", "), body = body
+    ).parse().unwrap()
+}
+
+/// Derives `HAccumulate` by accumulating every field.
+///
+/// Fields marked `#[ho(skip)]` keep the value of `self`.
+#[proc_macro_derive(HAccumulate, attributes(ho))]
+pub fn derive_h_accumulate(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    fieldwise(&s, "HAccumulate", &[
+        ("fn hadd(self, other: Self) -> Self", "hadd", "other.{}"),
+        ("fn hscale(self, s: f64) -> Self", "hscale", "s"),
+    ])
+}
+
+/// Derives `HDiff` by taking the difference of every field.
+///
+/// Fields marked `#[ho(skip)]` keep the value of `self`.
+#[proc_macro_derive(HDiff, attributes(ho))]
+pub fn derive_h_diff(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    fieldwise(&s, "HDiff", &[("fn hdiff(self, other: Self) -> Self", "hdiff", "other.{}")])
+}
+
+/// Derives `HDerivative` by taking the derivative of every field.
+///
+/// Fields marked `#[ho(skip)]` keep their value.
+#[proc_macro_derive(HDerivative, attributes(ho))]
+pub fn derive_h_derivative(input: TokenStream) -> TokenStream {
+    let s = match parse(input) {
+        Ok(s) => s,
+        Err(err) => return compile_error(&err),
+    };
+    fieldwise(&s, "HDerivative", &[("fn hderivative(self, h: f64) -> Self", "hderivative", "h")])
+}
+
+/// Derives the `HoLeaf` marker for a struct or enum without type parameters.
+#[proc_macro_derive(HoLeaf)]
+pub fn derive_ho_leaf(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let i = skip_attributes(&tokens, 0);
+    let i = parse_vis(&tokens, i).1;
+    let name = match (tokens.get(i), tokens.get(i + 1)) {
+        (Some(TokenTree::Ident(kw)), Some(TokenTree::Ident(id)))
+            if kw.to_string() == "struct" || kw.to_string() == "enum" => id.to_string(),
+        _ => return compile_error("HoLeaf can only be derived for structs and enums"),
+    };
+    if is_punct(tokens.get(i + 2), '<') {
+        return compile_error(&format!("HoLeaf can not be derived for generic type `{}`", name));
+    }
+    format!("impl ::ha::HoLeaf for {} {{}}", name).parse().unwrap()
+}
+
+/// Implements a trait for `Name<T>` by calling each method on every field.
+///
+/// A method is given by its signature, name and the other arguments,
+/// where `{}` is replaced by the field name.
+fn fieldwise(s: &Struct, trait_name: &str, methods: &[(&str, &str, &str)]) -> TokenStream {
+    let t = &s.param;
+    let bounds: Vec<String> = s.fields.iter().filter(|f| !f.skip)
+        .map(|f| format!(
+            "{x}: ::ha::Ho<{t}>, <{x} as ::ha::Ho<{t}>>::Fun: ::ha::{tr}", x = f.ty, t = t, tr = trait_name
+        ))
+        .collect();
+    let fns: Vec<String> = methods.iter()
+        .map(|(sig, method, args)| {
+            let values: Vec<String> = s.fields.iter()
+                .map(|f| {
+                    let value = if f.skip {
+                        format!("self.{}", f.name)
+                    } else {
+                        format!("::ha::{}::{}(self.{}, {})", trait_name, method, f.name, args.replace("{}", &f.name))
+                    };
+                    if s.named {format!("{}: {}", f.name, value)} else {value}
+                })
+                .collect();
+            let body = if s.named {
+                format!("Self {{{}}}", values.join(", "))
+            } else {
+                format!("Self({})", values.join(", "))
+            };
+            format!("{} {{{}}}", sig, body)
+        })
+        .collect();
+    format!(
+        "impl<{t}> ::ha::{tr} for {name}<{t}> where {bounds} {{
+            {fns}
+        }}",
+        t = t, tr = trait_name, name = s.name, bounds = bounds.join(", "), fns = fns.join("\n")
+    ).parse().unwrap()
+}
+
+/// A parsed struct.
+struct Struct {
+    vis: String,
+    name: String,
+    param: String,
+    /// Whether the fields are named, or else a tuple struct.
+    named: bool,
+    fields: Vec<Field>,
+}
+
+/// A parsed field.
+struct Field {
+    /// The field name, or index for tuple structs.
+    name: String,
+    /// The type `X` of `Fun<T, X>`, or the declared type of a skipped field.
+    ty: String,
+    /// Whether the field is marked `#[ho(skip)]`.
+    skip: bool,
+}
+
+fn compile_error(msg: &str) -> TokenStream {
+    format!("compile_error!({:?});", msg).parse().unwrap()
+}
+
+fn parse(input: TokenStream) -> Result<Struct, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut i = skip_attributes(&tokens, 0);
+    let (vis, next) = parse_vis(&tokens, i);
+    i = next;
+    match tokens.get(i) {
+        Some(TokenTree::Ident(id)) if id.to_string() == "struct" => i += 1,
+        _ => return Err("Ha derives only support structs".into()),
+    }
+    let name = match tokens.get(i) {
+        Some(TokenTree::Ident(id)) => id.to_string(),
+        _ => return Err("expected struct name".into()),
+    };
+    i += 1;
+    let mut generics = vec![];
+    if is_punct(tokens.get(i), '<') {
+        let end = closing_angle(&tokens, i)?;
+        generics = tokens[i + 1..end].to_vec();
+        i = end + 1;
+    }
+    let params: Vec<String> = split_commas(&generics).into_iter()
+        .filter_map(|p| match p.first() {
+            Some(TokenTree::Ident(id)) if id.to_string() != "const" => Some(id.to_string()),
+            _ => None,
+        })
+        .collect();
+    if params.len() != 1 {
+        return Err(format!("`{}` must have exactly one type parameter, e.g. `{}<T = ()>`", name, name));
+    }
+    let param = params.into_iter().next().unwrap();
+    let body = find_body(&tokens[i..]).ok_or_else(|| format!("`{}` has no fields", name))?;
+    let named = body.delimiter() == Delimiter::Brace;
+    let body: Vec<TokenTree> = body.stream().into_iter().collect();
+    let mut fields = vec![];
+    for (n, field) in split_commas(&body).into_iter().enumerate() {
+        let mut j = skip_attributes(&field, 0);
+        let skip = field[..j].iter().any(|t| match t {
+            TokenTree::Group(g) => g.stream().to_string().replace(' ', "") == "ho(skip)",
+            _ => false,
+        });
+        j = parse_vis(&field, j).1;
+        let field_name = if named {
+            let id = field.get(j).map(|t| t.to_string()).unwrap_or_default();
+            j += 2;
+            id
+        } else {
+            n.to_string()
+        };
+        let ty = &field[j.min(field.len())..];
+        let ty = if skip {
+            ty.iter().cloned().collect::<TokenStream>().to_string()
+        } else {
+            fun_output(ty, &param).ok_or_else(|| {
+                format!("field `{}` of `{}` must have the type `Fun<{}, X>`", field_name, name, param)
+            })?
+        };
+        fields.push(Field {name: field_name, ty, skip});
+    }
+    Ok(Struct {vis, name, param, named, fields})
+}
+
+/// Returns the fields group of a struct, given the tokens after the generics.
+///
+/// The where clause may contain groups too, e.g. `(f64, f64): Clone`,
+/// so the fields are the last brace group, or a parenthesis group
+/// followed by `;`, `where` or the end of a tuple struct.
+fn find_body(tokens: &[TokenTree]) -> Option<proc_macro::Group> {
+    let tuple = tokens.iter().enumerate().find_map(|(k, t)| match t {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Parenthesis => match tokens.get(k + 1) {
+            None => Some(g.clone()),
+            Some(TokenTree::Ident(id)) if id.to_string() == "where" => Some(g.clone()),
+            next if is_punct(next, ';') => Some(g.clone()),
+            _ => None,
+        },
+        _ => None,
+    });
+    tuple.or_else(|| tokens.iter().rev().find_map(|t| match t {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => Some(g.clone()),
+        _ => None,
+    }))
+}
+
+/// Returns the index after any outer attributes.
+fn skip_attributes(tokens: &[TokenTree], mut i: usize) -> usize {
+    while is_punct(tokens.get(i), '#') {i += 2}
+    i
+}
+
+/// Parses visibility, e.g. `pub` or `pub(crate)`.
+fn parse_vis(tokens: &[TokenTree], mut i: usize) -> (String, usize) {
+    let mut vis = String::new();
+    if let Some(TokenTree::Ident(id)) = tokens.get(i) {
+        if id.to_string() == "pub" {
+            vis.push_str("pub");
+            i += 1;
+            if let Some(TokenTree::Group(g)) = tokens.get(i) {
+                if g.delimiter() == Delimiter::Parenthesis {
+                    vis.push_str(&g.to_string());
+                    i += 1;
+                }
+            }
+        }
+    }
+    (vis, i)
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(p)) if p.as_char() == ch)
+}
+
+/// Returns the index of the `>` matching the `<` at `start`.
+fn closing_angle(tokens: &[TokenTree], start: usize) -> Result<usize, String> {
+    let mut depth = 0;
+    let mut arrow = false;
+    for (i, t) in tokens.iter().enumerate().skip(start) {
+        if let TokenTree::Punct(p) = t {
+            match p.as_char() {
+                '<' => depth += 1,
+                // The `>` of `->` does not close an angle bracket.
+                '>' if !arrow => {
+                    depth -= 1;
+                    if depth == 0 {return Ok(i)}
+                }
+                _ => {}
+            }
+            arrow = p.as_char() == '-' && p.spacing() == Spacing::Joint;
+        } else {
+            arrow = false;
+        }
+    }
+    Err("unbalanced angle brackets".into())
+}
+
+/// Splits tokens by commas outside of angle brackets.
+fn split_commas(tokens: &[TokenTree]) -> Vec<Vec<TokenTree>> {
+    let mut res = vec![];
+    let mut cur = vec![];
+    let mut depth = 0;
+    let mut arrow = false;
+    for t in tokens {
+        if let TokenTree::Punct(p) = t {
+            match p.as_char() {
+                '<' => depth += 1,
+                '>' if !arrow => depth -= 1,
+                ',' if depth == 0 => {
+                    res.push(std::mem::take(&mut cur));
+                    arrow = false;
+                    continue;
+                }
+                _ => {}
+            }
+            arrow = p.as_char() == '-' && p.spacing() == Spacing::Joint;
+        } else {
+            arrow = false;
+        }
+        cur.push(t.clone());
+    }
+    if !cur.is_empty() {res.push(cur)}
+    res
+}
+
+/// Returns `X` from the type `Fun<T, X>`, where `T` is `param`.
+fn fun_output(ty: &[TokenTree], param: &str) -> Option<String> {
+    let start = ty.iter().position(|t| is_punct(Some(t), '<'))?;
+    match ty.get(start.checked_sub(1)?) {
+        Some(TokenTree::Ident(id)) if id.to_string() == "Fun" => {}
+        _ => return None,
+    }
+    let end = closing_angle(ty, start).ok()?;
+    if end + 1 != ty.len() {return None};
+    let args = split_commas(&ty[start + 1..end]);
+    match &args[..] {
+        [t, x] if t.len() == 1 && t[0].to_string() == param => {
+            Some(x.iter().cloned().collect::<TokenStream>().to_string())
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,159 @@
+//! Numerical analysis of higher order functions.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Arg, Call, Func, HAccumulate, Ho};
+
+/// Computes the total variation of a function over `[a, b]`.
+///
+/// The function is sampled uniformly at `n + 1` points,
+/// and the absolute differences between consecutive samples are summed.
+pub fn total_variation(f: &Func<f64, f64>, a: f64, b: f64, n: usize) -> f64 {
+    let mut sum = 0.0;
+    let mut prev = f(a);
+    for i in 1..=n {
+        let next = f(a + (b - a) * i as f64 / n as f64);
+        sum += (next - prev).abs();
+        prev = next;
+    }
+    sum
+}
+
+/// Estimates the integral of a function over `[a, b]` by random sampling.
+///
+/// The estimate is the mean of the samples times the width of the interval.
+/// Samples are drawn from a pseudo-random generator seeded by `seed`,
+/// such that the same seed always gives the same estimate.
+pub fn monte_carlo(f: &Func<f64, f64>, a: f64, b: f64, samples: usize, seed: u64) -> f64 {
+    if samples == 0 {return 0.0};
+    let mut state = seed;
+    let mut sum = 0.0;
+    for _ in 0..samples {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let u = (splitmix64(state) >> 11) as f64 / (1u64 << 53) as f64;
+        sum += f(a + (b - a) * u);
+    }
+    sum / samples as f64 * (b - a)
+}
+
+/// Mixes the bits of a 64-bit value.
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Checks whether a sampled function is monotonic over `[a, b]`.
+///
+/// The function is sampled uniformly at `n + 1` points.
+/// Returns `Some(true)` when non-decreasing, `Some(false)` when non-increasing,
+/// and `None` when neither. A flat function counts as non-decreasing.
+pub fn is_monotonic(f: &Func<f64, f64>, a: f64, b: f64, n: usize) -> Option<bool> {
+    let mut increasing = true;
+    let mut decreasing = true;
+    let mut prev = f(a);
+    for i in 1..=n {
+        let next = f(a + (b - a) * i as f64 / n as f64);
+        if next < prev {increasing = false};
+        if next > prev {decreasing = false};
+        prev = next;
+    }
+    if increasing {Some(true)} else if decreasing {Some(false)} else {None}
+}
+
+/// Averages a higher order value field-wise over `[a, b]`.
+///
+/// Uses the midpoint rule with `n` samples.
+/// Multiply by `b - a` to get the integral.
+///
+/// Panics if `n` is zero.
+pub fn integrate_struct<H>(f: &<H as Ho<Arg<f64>>>::Fun, a: f64, b: f64, n: usize) -> H
+    where H: Call<f64> + HAccumulate
+{
+    assert!(n > 0, "integrate_struct requires at least one sample");
+    let dt = (b - a) / n as f64;
+    (1..n).map(|i| H::call(f, a + (i as f64 + 0.5) * dt))
+        .fold(H::call(f, a + 0.5 * dt), |acc, x| acc.hadd(x))
+        .hscale(1.0 / n as f64)
+}
+
+/// Fits a polynomial to a function over `[a, b]` by least squares.
+///
+/// The function is sampled uniformly at `samples` points.
+/// Returns `degree + 1` coefficients in ascending order of power,
+/// which can be passed to `polynomial`.
+pub fn fit_polynomial(
+    f: &Func<f64, f64>,
+    degree: usize,
+    a: f64,
+    b: f64,
+    samples: usize
+) -> Vec<f64> {
+    let m = degree + 1;
+    // Normal equations with the right hand side in the last column.
+    let mut mat = vec![vec![0.0; m + 1]; m];
+    for i in 0..samples {
+        let t = if samples > 1 {a + (b - a) * i as f64 / (samples - 1) as f64} else {a};
+        let y = f(t);
+        let powers: Vec<f64> = (0..2 * m).scan(1.0, |p, _| {
+            let cur = *p;
+            *p *= t;
+            Some(cur)
+        }).collect();
+        for (r, row) in mat.iter_mut().enumerate() {
+            for c in 0..m {row[c] += powers[r + c]};
+            row[m] += y * powers[r];
+        }
+    }
+    // Gaussian elimination with partial pivoting.
+    for col in 0..m {
+        let pivot = (col..m).max_by(|&i, &j| mat[i][col].abs().total_cmp(&mat[j][col].abs()))
+            .unwrap();
+        mat.swap(col, pivot);
+        if mat[col][col] == 0.0 {continue};
+        let (top, rest) = mat.split_at_mut(col + 1);
+        let pivot_row = &top[col];
+        for row in rest {
+            let k = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {*x -= k * p};
+        }
+    }
+    let mut coeffs = vec![0.0; m];
+    for r in (0..m).rev() {
+        if mat[r][r] == 0.0 {continue};
+        let s: f64 = (r + 1..m).map(|c| mat[r][c] * coeffs[c]).sum();
+        coeffs[r] = (mat[r][m] - s) / mat[r][r];
+    }
+    coeffs
+}
+
+/// Samples a higher order value into a table.
+///
+/// Returns `n` samples spaced uniformly over `[a, b]`, including both ends.
+pub fn snapshot<H: Call<f64>>(f: &<H as Ho<Arg<f64>>>::Fun, a: f64, b: f64, n: usize) -> Vec<H> {
+    (0..n).map(|i| {
+        let t = if n > 1 {a + (b - a) * i as f64 / (n - 1) as f64} else {a};
+        H::call(f, t)
+    }).collect()
+}
+
+/// Finds the seams of a function over `[a, b]`.
+///
+/// The function is sampled uniformly at `n + 1` points.
+/// For every pair of consecutive samples that differ by more than `jump_tol`,
+/// the parameter value halfway between them is reported.
+pub fn discontinuities(f: &Func<f64, f64>, a: f64, b: f64, n: usize, jump_tol: f64) -> Vec<f64> {
+    let mut res = vec![];
+    let mut prev_t = a;
+    let mut prev = f(a);
+    for i in 1..=n {
+        let t = a + (b - a) * i as f64 / n as f64;
+        let next = f(t);
+        if (next - prev).abs() > jump_tol {res.push(0.5 * (prev_t + t))};
+        prev_t = t;
+        prev = next;
+    }
+    res
+}
@@ -0,0 +1,279 @@
+//! Combinators for higher order functions.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{identity, Func};
+#[cfg(feature = "std")]
+use crate::Length;
+use crate::analysis::splitmix64;
+
+/// Returns the product of two functions.
+///
+/// The functions are called independently on each component of the argument.
+pub fn product<A, B, X, Y>(f: Func<A, X>, g: Func<B, Y>) -> Func<(A, B), (X, Y)>
+    where A: 'static, B: 'static, X: 'static, Y: 'static
+{
+    Arc::new(move |(a, b)| (f(a), g(b)))
+}
+
+/// Scales the time argument of a function by a constant factor.
+///
+/// A factor above `1` speeds up the function, while a factor below `1` slows it down.
+pub fn time_scale<U: 'static>(f: Func<f64, U>, factor: f64) -> Func<f64, U> {
+    Arc::new(move |t| f(t * factor))
+}
+
+/// Composes two functions into one, applying `f` first and then `g`.
+pub fn compose<A, B, C>(f: Func<A, B>, g: Func<B, C>) -> Func<A, C>
+    where A: 'static, B: 'static, C: 'static
+{
+    Arc::new(move |a| g(f(a)))
+}
+
+/// Composes a sequence of functions into one.
+///
+/// The functions are applied from left to right.
+/// An empty sequence returns the input unchanged.
+pub fn compose_all(fs: Vec<Func<f64, f64>>) -> Func<f64, f64> {
+    fs.into_iter().fold(identity(), compose)
+}
+
+/// Returns the numerical derivative of a function at `t`.
+///
+/// The derivative is computed by central difference with step size `h`.
+pub fn derivative(f: impl Fn(f64) -> f64, t: f64, h: f64) -> f64 {
+    (f(t + h) - f(t - h)) / (2.0 * h)
+}
+
+/// Returns the value of a function together with its numerical derivative.
+///
+/// The derivative is computed by central difference with step size `h`.
+pub fn with_gradient(f: Func<f64, f64>, h: f64) -> Func<f64, (f64, f64)> {
+    Arc::new(move |t| (f(t), derivative(&*f, t, h)))
+}
+
+/// Transforms the first component of a function returning a pair.
+pub fn map_first<T, A, B, C>(
+    f: Func<T, (A, B)>,
+    g: impl Fn(A) -> C + Send + Sync + 'static
+) -> Func<T, (C, B)>
+    where T: 'static, A: 'static, B: 'static
+{
+    Arc::new(move |t| {
+        let (a, b) = f(t);
+        (g(a), b)
+    })
+}
+
+/// Transforms the second component of a function returning a pair.
+pub fn map_second<T, A, B, C>(
+    f: Func<T, (A, B)>,
+    g: impl Fn(B) -> C + Send + Sync + 'static
+) -> Func<T, (A, C)>
+    where T: 'static, A: 'static, B: 'static
+{
+    Arc::new(move |t| {
+        let (a, b) = f(t);
+        (a, g(b))
+    })
+}
+
+/// Selects among several functions by an index function.
+///
+/// The index is clamped to the range of options.
+///
+/// Panics if `options` is empty.
+pub fn switch<T, U>(selector: Func<T, usize>, options: Vec<Func<T, U>>) -> Func<T, U>
+    where T: Clone + 'static, U: 'static
+{
+    assert!(!options.is_empty(), "switch requires at least one option");
+    Arc::new(move |t: T| {
+        let i = selector(t.clone()).min(options.len() - 1);
+        options[i](t)
+    })
+}
+
+/// Averages a function over a window of neighboring indices.
+///
+/// The window covers `radius` indices on each side, clamped at `0`.
+pub fn moving_average(f: Func<usize, f64>, radius: usize) -> Func<usize, f64> {
+    Arc::new(move |i| {
+        let start = i.saturating_sub(radius);
+        let end = i.saturating_add(radius);
+        let sum: f64 = (start..=end).map(|j| f(j)).sum();
+        sum / ((end - start) as f64 + 1.0)
+    })
+}
+
+/// Lifts a scalar function to a function applied to every element of a vector.
+pub fn vectorize(f: Func<f64, f64>) -> Func<Vec<f64>, Vec<f64>> {
+    Arc::new(move |v: Vec<f64>| v.into_iter().map(|t| f(t)).collect())
+}
+
+/// Computes the inverse of a monotonic function numerically.
+///
+/// Each call searches the `domain` by bisection until the interval is smaller than `tol`.
+/// The function must be monotonic over the domain, either increasing or decreasing.
+/// Values outside the range of the function return the nearest end of the domain.
+pub fn invert(f: Func<f64, f64>, domain: (f64, f64), tol: f64) -> Func<f64, f64> {
+    Arc::new(move |y| {
+        let (mut lo, mut hi) = domain;
+        let increasing = f(lo) <= f(hi);
+        // Enough halvings to exhaust the precision of `f64`.
+        for _ in 0..1100 {
+            if hi - lo <= tol {break};
+            let mid = 0.5 * (lo + hi);
+            if (f(mid) < y) == increasing {lo = mid} else {hi = mid}
+        }
+        0.5 * (lo + hi)
+    })
+}
+
+/// Cross-fades through a sequence of functions over the unit interval.
+///
+/// The unit interval is divided into equal segments between consecutive stages.
+/// Within a segment, the two stages are evaluated at the same input and blended linearly.
+/// A single stage is returned unchanged, and no stages give a constant `0`.
+#[cfg(feature = "std")]
+pub fn morph(stages: Vec<Func<f64, f64>>) -> Func<f64, f64> {
+    match stages.len() {
+        0 => return Arc::new(|_| 0.0),
+        1 => return stages[0].clone(),
+        _ => {}
+    }
+    Arc::new(move |t| {
+        let segments = (stages.len() - 1) as f64;
+        let x = t.clamp(0.0, 1.0) * segments;
+        let i = (x.floor() as usize).min(stages.len() - 2);
+        let s = x - i as f64;
+        let a = stages[i](t);
+        a + (stages[i + 1](t) - a) * s
+    })
+}
+
+/// Adds a deterministic pseudo-random offset to the output of a function.
+///
+/// The offset lies in `[-amplitude, amplitude]` and is derived from the bits of the input and `seed`,
+/// such that the same input always gets the same offset.
+pub fn jitter(f: Func<f64, f64>, amplitude: f64, seed: u64) -> Func<f64, f64> {
+    Arc::new(move |t: f64| {
+        let h = splitmix64(t.to_bits() ^ splitmix64(seed));
+        let u = (h >> 11) as f64 / (1u64 << 53) as f64;
+        f(t) + amplitude * (2.0 * u - 1.0)
+    })
+}
+
+/// Computes the gradient of a 2D scalar function numerically.
+///
+/// Uses central differences with step size `h` along each axis.
+pub fn gradient_2d(f: Func<[f64; 2], f64>, h: f64) -> Func<[f64; 2], [f64; 2]> {
+    Arc::new(move |[x, y]: [f64; 2]| [
+        derivative(|x| f([x, y]), x, h),
+        derivative(|y| f([x, y]), y, h),
+    ])
+}
+
+/// Quantizes the output of a function to a number of discrete levels.
+///
+/// The levels are spaced equally over `range`, including both ends,
+/// and each output snaps to the nearest level.
+/// With `levels <= 1`, the output is the midpoint of the range.
+/// An empty range always outputs its single level.
+#[cfg(feature = "std")]
+pub fn posterize(f: Func<f64, f64>, levels: usize, range: (f64, f64)) -> Func<f64, f64> {
+    let (lo, hi) = range;
+    Arc::new(move |t| {
+        if levels <= 1 {return 0.5 * (lo + hi)};
+        if hi == lo {return lo};
+        let steps = (levels - 1) as f64;
+        let s = ((f(t) - lo) / (hi - lo)).clamp(0.0, 1.0);
+        lo + (s * steps).round() / steps * (hi - lo)
+    })
+}
+
+/// Reparameterizes a 2D curve over `[0, 1]` by arc length.
+///
+/// Equal steps of the input give equal steps of length along the curve.
+/// The arc length is tabulated from `samples` segments,
+/// and the table is inverted per call by binary search.
+#[cfg(feature = "std")]
+pub fn by_arc_length(curve: Func<f64, [f64; 2]>, samples: usize) -> Func<f64, [f64; 2]> {
+    let n = samples.max(1);
+    let mut table = Vec::with_capacity(n + 1);
+    let mut len = 0.0;
+    let mut prev = curve(0.0);
+    table.push(0.0);
+    for i in 1..=n {
+        let p = curve(i as f64 / n as f64);
+        len += [p[0] - prev[0], p[1] - prev[1]].length();
+        table.push(len);
+        prev = p;
+    }
+    Arc::new(move |s: f64| {
+        let target = s.clamp(0.0, 1.0) * len;
+        let i = table.partition_point(|&l| l < target).clamp(1, n);
+        let (l0, l1) = (table[i - 1], table[i]);
+        let frac = if l1 > l0 {(target - l0) / (l1 - l0)} else {0.0};
+        curve((i as f64 - 1.0 + frac) / n as f64)
+    })
+}
+
+/// Normalizes the input angle of a function to `[0, 2π)` before evaluation.
+#[cfg(feature = "std")]
+pub fn angular(f: Func<f64, f64>) -> Func<f64, f64> {
+    Arc::new(move |t| f(t.rem_euclid(2.0 * core::f64::consts::PI)))
+}
+
+/// Returns the running integral of a function from `0` to the input.
+///
+/// Each call integrates by the trapezoidal rule with `steps_per_unit` steps per unit of input,
+/// so the cost of a call grows with the magnitude of the input.
+/// For hot paths, sample the result with `snapshot` and interpolate with `table_lookup`.
+/// A NaN or infinite input returns NaN instead of taking unbounded steps.
+#[cfg(feature = "std")]
+pub fn antiderivative(f: Func<f64, f64>, steps_per_unit: usize) -> Func<f64, f64> {
+    Arc::new(move |t| {
+        if !t.is_finite() {return f64::NAN};
+        let n = ((t.abs() * steps_per_unit as f64).ceil() as usize).max(1);
+        let h = t / n as f64;
+        let inner: f64 = (1..n).map(|i| f(h * i as f64)).sum();
+        h * (0.5 * (f(0.0) + f(t)) + inner)
+    })
+}
+
+/// Replaces outputs that are NaN or infinite with `fallback`.
+pub fn nan_safe(f: Func<f64, f64>, fallback: f64) -> Func<f64, f64> {
+    Arc::new(move |t| {
+        let u = f(t);
+        if u.is_finite() {u} else {fallback}
+    })
+}
+
+/// Plays a sequence of clips back to back, cross-fading between them.
+///
+/// Each clip is a `(duration, clip)` pair and is called with the time since it started.
+/// Consecutive clips overlap by `crossfade` and are blended linearly over the overlap,
+/// so the total time is the sum of durations minus the overlaps.
+/// Times before the start or after the end extend the first or last clip.
+/// No clips give a constant `0`.
+///
+/// Panics if `crossfade` is longer than any clip.
+pub fn timeline(clips: Vec<(f64, Func<f64, f64>)>, crossfade: f64) -> Func<f64, f64> {
+    if clips.is_empty() {return Arc::new(|_| 0.0)};
+    let crossfade = crossfade.max(0.0);
+    assert!(clips.iter().all(|c| crossfade <= c.0), "timeline crossfade is longer than a clip");
+    let mut starts = Vec::with_capacity(clips.len());
+    let mut start = 0.0;
+    for (duration, _) in &clips {
+        starts.push(start);
+        start += duration - crossfade;
+    }
+    Arc::new(move |t| {
+        let i = starts.partition_point(|&s| s <= t).max(1) - 1;
+        let b = clips[i].1(t - starts[i]);
+        if i == 0 || t >= starts[i] + crossfade {return b};
+        let a = clips[i - 1].1(t - starts[i - 1]);
+        a + (b - a) * (t - starts[i]) / crossfade
+    })
+}
@@ -0,0 +1,187 @@
+//! Constructors for common higher order functions.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+use crate::Func;
+
+/// Creates a function that ignores its input and returns a constant.
+pub fn konst<T, U>(u: U) -> Func<T, U>
+    where U: Clone + Send + Sync + 'static
+{
+    Arc::new(move |_| u.clone())
+}
+
+/// Creates a function that returns its input unchanged.
+pub fn identity<T: 'static>() -> Func<T, T> {
+    Arc::new(|t| t)
+}
+
+/// Creates a function from a lookup table.
+///
+/// Keys missing from the table return the default value.
+#[cfg(feature = "std")]
+pub fn from_map<K, U>(table: HashMap<K, U>, default: U) -> Func<K, U>
+    where K: Eq + Hash + Send + Sync + 'static,
+          U: Clone + Send + Sync + 'static
+{
+    Arc::new(move |k| table.get(&k).unwrap_or(&default).clone())
+}
+
+/// Creates a color gradient from `(position, rgb)` stops.
+///
+/// Colors are linearly interpolated between stops.
+/// Arguments outside the stops are clamped to the first or last color.
+/// An empty list of stops returns black.
+/// A NaN argument returns NaN for every channel.
+pub fn gradient(mut stops: Vec<(f64, [f64; 3])>) -> Func<f64, [f64; 3]> {
+    stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Arc::new(move |t| {
+        let (first, last) = match (stops.first(), stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return [0.0; 3],
+        };
+        if t <= first.0 {return first.1};
+        if t >= last.0 {return last.1};
+        let i = match stops.iter().position(|s| s.0 > t) {
+            Some(i) => i,
+            None => return [f64::NAN; 3],
+        };
+        let (t0, a) = stops[i - 1];
+        let (t1, b) = stops[i];
+        let s = (t - t0) / (t1 - t0);
+        [
+            a[0] + (b[0] - a[0]) * s,
+            a[1] + (b[1] - a[1]) * s,
+            a[2] + (b[2] - a[2]) * s,
+        ]
+    })
+}
+
+/// Creates a step function returning the value of the nearest sample.
+///
+/// Ties are broken toward the sample with lower argument.
+/// An empty list of samples returns NaN.
+pub fn nearest(mut points: Vec<(f64, f64)>) -> Func<f64, f64> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Arc::new(move |t| {
+        let i = points.partition_point(|p| p.0 < t);
+        match (i.checked_sub(1).map(|j| points[j]), points.get(i)) {
+            (Some(a), Some(b)) => if t - a.0 <= b.0 - t {a.1} else {b.1},
+            (Some(a), None) => a.1,
+            (None, Some(b)) => b.1,
+            (None, None) => f64::NAN,
+        }
+    })
+}
+
+/// Creates a damped sine oscillation.
+///
+/// The amplitude envelope is `exp(-decay * t)`, starting at `1` for `t = 0`.
+/// The oscillation has frequency `freq` in cycles per unit of `t`.
+#[cfg(feature = "std")]
+pub fn damped_sine(freq: f64, decay: f64) -> Func<f64, f64> {
+    Arc::new(move |t| (-decay * t).exp() * (2.0 * core::f64::consts::PI * freq * t).sin())
+}
+
+/// Creates a bell curve with its peak of `1` at `mean`.
+///
+/// Panics if `std_dev` is not positive.
+#[cfg(feature = "std")]
+pub fn gaussian(mean: f64, std_dev: f64) -> Func<f64, f64> {
+    assert!(std_dev > 0.0, "gaussian requires a positive standard deviation");
+    let denom = 2.0 * std_dev * std_dev;
+    Arc::new(move |t| (-(t - mean) * (t - mean) / denom).exp())
+}
+
+/// Creates the probability density of a normal distribution.
+///
+/// This is the same as `gaussian`, divided by `std_dev * sqrt(2π)`.
+/// Panics if `std_dev` is not positive.
+#[cfg(feature = "std")]
+pub fn gaussian_normalized(mean: f64, std_dev: f64) -> Func<f64, f64> {
+    let f = gaussian(mean, std_dev);
+    let scale = 1.0 / (std_dev * (2.0 * core::f64::consts::PI).sqrt());
+    Arc::new(move |t| f(t) * scale)
+}
+
+/// Creates a logistic sigmoid curve.
+///
+/// The output lies in the open range `(0, 1)` and equals `0.5` at `midpoint`.
+/// Higher `steepness` gives a sharper transition.
+#[cfg(feature = "std")]
+pub fn sigmoid(steepness: f64, midpoint: f64) -> Func<f64, f64> {
+    Arc::new(move |t| 1.0 / (1.0 + (-steepness * (t - midpoint)).exp()))
+}
+
+/// Creates a piecewise linear function from a table sorted by argument.
+///
+/// The interval is located by binary search, which makes lookups `O(log n)`.
+/// Arguments outside the table are clamped to the first or last value.
+/// An empty table or a NaN argument returns NaN.
+///
+/// The table must be sorted by argument, which is checked in debug builds.
+pub fn table_lookup(sorted: Vec<(f64, f64)>) -> Func<f64, f64> {
+    debug_assert!(sorted.windows(2).all(|w| w[0].0 <= w[1].0), "table must be sorted");
+    Arc::new(move |t| {
+        if t.is_nan() {return f64::NAN};
+        let i = sorted.partition_point(|p| p.0 <= t);
+        if i == 0 {
+            return sorted.first().map(|p| p.1).unwrap_or(f64::NAN);
+        }
+        if i == sorted.len() {return sorted[i - 1].1};
+        let (t0, a) = sorted[i - 1];
+        let (t1, b) = sorted[i];
+        a + (b - a) * (t - t0) / (t1 - t0)
+    })
+}
+
+/// Creates a polynomial from coefficients in ascending order of power.
+///
+/// For example, `vec![1.0, 2.0, 3.0]` gives `1 + 2x + 3x²`.
+pub fn polynomial(coeffs: Vec<f64>) -> Func<f64, f64> {
+    Arc::new(move |t| coeffs.iter().rev().fold(0.0, |acc, c| acc * t + c))
+}
+
+/// Creates a sequence defined by a recurrence relation.
+///
+/// The sequence starts with `seed`, and each following value is computed by `step`
+/// from a window holding the previous `seed.len()` values.
+/// Computed values are cached.
+/// The cache is not locked while `step` runs, so `step` may panic without poisoning it.
+/// Concurrent callers may compute the same value, but only one result is kept.
+#[cfg(feature = "std")]
+pub fn recurrence(
+    seed: Vec<f64>,
+    step: impl Fn(&[f64]) -> f64 + Send + Sync + 'static
+) -> Func<usize, f64> {
+    let window = seed.len();
+    let cache = Mutex::new(seed);
+    Arc::new(move |n| loop {
+        let (len, last) = {
+            let seq = cache.lock().unwrap();
+            if n < seq.len() {return seq[n]};
+            (seq.len(), seq[seq.len() - window..].to_vec())
+        };
+        let next = step(&last);
+        let mut seq = cache.lock().unwrap();
+        if seq.len() == len {seq.push(next)};
+    })
+}
+
+/// Creates the signed distance function of a sphere.
+///
+/// The distance is negative inside the sphere, zero on the surface and positive outside.
+#[cfg(feature = "std")]
+pub fn sphere_sdf(center: [f64; 3], radius: f64) -> Func<[f64; 3], f64> {
+    Arc::new(move |p: [f64; 3]| {
+        let d = [p[0] - center[0], p[1] - center[1], p[2] - center[2]];
+        (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt() - radius
+    })
+}
@@ -1,4 +1,6 @@
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
 //! # Higher Order Core
 //!
@@ -168,6 +170,45 @@
 //! - Once for the ordinary case `X<()>`
 //! - Once for the higher order case `X<Arg<T>>`
 //!
+//! When the higher order case only calls the values and applies the ordinary case,
+//! the `higher_order_op!` macro generates both impls from the ordinary case.
+//!
+//! With the `derive` feature, `#[derive(Ho)]` generates the `Ho<Arg<T>>` impl
+//! and the type alias `PointFunc<T>` for a struct declared like `Point` above,
+//! and `#[derive(Call)]` generates the `Call<T>` impl.
+#![cfg_attr(feature = "derive", doc = r#"
+The derives reject structs of any other shape at compile time.
+A field must have the type `Fun<T, X>`:
+
+```compile_fail
+use ha::{Fun, Ho};
+
+#[derive(Clone, Ho)]
+struct Point<T = ()> where f64: Ho<T> {
+    x: Fun<T, f64>,
+    y: f64,
+}
+```
+
+`HoLeaf` can not be derived for a generic type:
+
+```compile_fail
+#[derive(Clone, ha::HoLeaf)]
+struct Wrapper<T>(T);
+```
+
+`Ho` and `Call` can not be derived for an enum:
+
+```compile_fail
+use ha::{Call, Fun, Ho};
+
+#[derive(Clone, Ho, Call)]
+enum Shape<T = ()> where f64: Ho<T> {
+    Circle(Fun<T, f64>),
+}
+```
+"#)]
+//!
 //! ### Higher Order Maps
 //!
 //! Sometimes it is useful to construct arbitrary data of the kind:
@@ -207,11 +248,79 @@
 //! let q: [f64; 2] = args.hmap(&in_between);
 //! ```
 
-use std::sync::Arc;
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::num::Saturating;
+
+pub use analysis::*;
+pub use combinators::*;
+pub use constructors::*;
+pub use maps::*;
+pub use ops::*;
+pub use stateful::*;
+
+mod analysis;
+mod combinators;
+mod constructors;
+mod macros;
+mod maps;
+mod ops;
+mod stateful;
+
+#[cfg(feature = "parallel")]
+pub use parallel::*;
+
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "simd")]
+pub mod simd;
+
+#[cfg(feature = "derive")]
+pub use ha_derive::{Call, HAccumulate, HDerivative, HDiff, Ho, HoBuilder, HoFields, HoLeaf};
 
 /// Standard function type.
 pub type Func<T, U> = Arc<dyn Fn(T) -> U + Send + Sync>;
 
+/// Creates a function from a closure.
+///
+/// This avoids wrapping every field of a higher order structure in `Arc::new`.
+/// A constructor taking one closure per field can be written on top of it:
+///
+/// ```rust
+/// use ha::{func, Arg, Fun, Ho};
+///
+/// #[derive(Clone)]
+/// pub struct Point2<T = ()> where f64: Ho<T> {
+///     pub x: Fun<T, f64>,
+///     pub y: Fun<T, f64>,
+/// }
+///
+/// impl<T> Point2<Arg<T>> {
+///     /// Creates a higher order point from one function per coordinate.
+///     pub fn from_fns(
+///         fx: impl Fn(T) -> f64 + Send + Sync + 'static,
+///         fy: impl Fn(T) -> f64 + Send + Sync + 'static,
+///     ) -> Self where T: 'static {
+///         Point2 {x: func(fx), y: func(fy)}
+///     }
+/// }
+///
+/// let p = Point2::from_fns(|t: f64| 2.0 * t, |t| t + 1.0);
+/// assert_eq!(((p.x)(1.5), (p.y)(1.5)), (3.0, 2.5));
+/// ```
+///
+/// With the `derive` feature, `#[derive(HoBuilder)]` generates `from_fns`.
+pub fn func<T, U>(f: impl Fn(T) -> U + Send + Sync + 'static) -> Func<T, U> {
+    Arc::new(f)
+}
+
 /// Used to disambiguate impls for Rust's type checker.
 #[derive(Copy, Clone)]
 pub struct Arg<T>(pub T);
@@ -241,23 +350,180 @@ where U: Ho<Arg<T>, Fun = Func<T, Self>> {
     fn call(f: &Self::Fun, val: T) -> Self {f(val)}
 }
 
+/// Implemented by higher order calls with two arguments.
+///
+/// The function type is the same as for a single tupled argument,
+/// e.g. a surface `PointFunc<(f64, f64)>` is called with `(u, v)` as `call2(&f, u, v)`.
+pub trait Call2<A, B>: Ho<Arg<(A, B)>> {
+    /// Calls function with two values.
+    fn call2(f: &Self::Fun, a: A, b: B) -> Self;
+}
+
+impl<A, B, U> Call2<A, B> for U
+where U: Call<(A, B)> {
+    fn call2(f: &Self::Fun, a: A, b: B) -> Self {
+        <U as Call<(A, B)>>::call(f, (a, b))
+    }
+}
+
+/// Converts a higher order value into a closure.
+///
+/// This is useful for passing higher order values to APIs expecting a plain `Fn`.
+pub fn into_closure<H: Call<T>, T>(f: <H as Ho<Arg<T>>>::Fun) -> impl Fn(T) -> H {
+    move |val| H::call(&f, val)
+}
+
+/// Converts a higher order value into a closure that transforms each result.
+pub fn post_call<H: Call<T>, T, H2>(
+    f: <H as Ho<Arg<T>>>::Fun,
+    g: impl Fn(H) -> H2
+) -> impl Fn(T) -> H2 {
+    move |val| g(H::call(&f, val))
+}
+
+/// Calls every higher order value with the same argument.
+pub fn call_each<H: Call<T>, T: Clone>(fs: &[<H as Ho<Arg<T>>>::Fun], val: T) -> Vec<H> {
+    fs.iter().map(|f| H::call(f, val.clone())).collect()
+}
+
 impl<T: Clone> Ho<()> for T {type Fun = T;}
 
 /// Used to declare functions in a more readable way.
 pub type Fun<T, U> = <U as Ho<T>>::Fun;
 
-impl<T> Ho<Arg<T>> for f64 {type Fun = Func<T, f64>;}
-impl<T> Ho<Arg<T>> for f32 {type Fun = Func<T, f32>;}
-impl<T> Ho<Arg<T>> for u8 {type Fun = Func<T, u8>;}
-impl<T> Ho<Arg<T>> for u16 {type Fun = Func<T, u16>;}
-impl<T> Ho<Arg<T>> for u32 {type Fun = Func<T, u32>;}
-impl<T> Ho<Arg<T>> for u64 {type Fun = Func<T, u64>;}
-impl<T> Ho<Arg<T>> for usize {type Fun = Func<T, usize>;}
-impl<T> Ho<Arg<T>> for i8 {type Fun = Func<T, i8>;}
-impl<T> Ho<Arg<T>> for i16 {type Fun = Func<T, i16>;}
-impl<T> Ho<Arg<T>> for i32 {type Fun = Func<T, i32>;}
-impl<T> Ho<Arg<T>> for i64 {type Fun = Func<T, i64>;}
-impl<T> Ho<Arg<T>> for isize {type Fun = Func<T, isize>;}
+/// Implemented by types that are higher order values with a plain function type.
+///
+/// For any argument type `T`, the function type of a leaf `V` is `Func<T, V>`.
+/// This makes it possible to use custom types as constant fields of higher order structures.
+/// With the `derive` feature, this can be implemented with `#[derive(HoLeaf)]`.
+pub trait HoLeaf: Clone + Send + Sync + 'static {}
+
+impl<T, V: HoLeaf> Ho<Arg<T>> for V {type Fun = Func<T, V>;}
+
+impl HoLeaf for f64 {}
+impl HoLeaf for f32 {}
+impl HoLeaf for u8 {}
+impl HoLeaf for u16 {}
+impl HoLeaf for u32 {}
+impl HoLeaf for u64 {}
+impl HoLeaf for usize {}
+impl HoLeaf for i8 {}
+impl HoLeaf for i16 {}
+impl HoLeaf for i32 {}
+impl HoLeaf for i64 {}
+impl HoLeaf for isize {}
+impl HoLeaf for bool {}
+impl HoLeaf for char {}
+impl HoLeaf for String {}
+impl HoLeaf for Saturating<u8> {}
+impl HoLeaf for Saturating<u16> {}
+impl HoLeaf for Saturating<u32> {}
+impl HoLeaf for Saturating<u64> {}
+impl HoLeaf for Saturating<usize> {}
+impl HoLeaf for Saturating<i8> {}
+impl HoLeaf for Saturating<i16> {}
+impl HoLeaf for Saturating<i32> {}
+impl HoLeaf for Saturating<i64> {}
+impl HoLeaf for Saturating<isize> {}
+
+/// Function type for higher order values with internal state.
+pub type FuncMut<T, U> = Box<dyn FnMut(T) -> U + Send>;
+
+/// Implemented by higher order types that support mutable evaluation.
+///
+/// This is the counterpart of `Ho` for stateful generators, such as counters.
+/// Since the function type can be mutated, it is not required to be `Clone`.
+pub trait HoMut<T>: Sized {
+    /// The function type.
+    type Fun;
+}
+
+/// Implemented by higher order calls that mutate the function.
+pub trait CallMut<T>: HoMut<Arg<T>> {
+    /// Calls function with some value.
+    fn call_mut(f: &mut Self::Fun, val: T) -> Self;
+}
+
+impl<T, U> CallMut<T> for U
+where U: HoMut<Arg<T>, Fun = FuncMut<T, Self>> {
+    fn call_mut(f: &mut Self::Fun, val: T) -> Self {f(val)}
+}
+
+impl<T> HoMut<()> for T {type Fun = T;}
+
+impl<T, V: HoLeaf> HoMut<Arg<T>> for V {type Fun = FuncMut<T, V>;}
+
+/// Function type for higher order values with single ownership.
+///
+/// Unlike `Func`, this does not use reference counting and can not be cloned.
+pub type FuncBox<T, U> = Box<dyn Fn(T) -> U + Send + Sync>;
+
+/// Implemented by higher order types with single ownership of their functions.
+///
+/// This is the counterpart of `Ho` for values that are never shared or cloned.
+pub trait BoxHo<T>: Sized {
+    /// The function type.
+    type Fun;
+}
+
+/// Implemented by higher order calls with single ownership of their functions.
+pub trait BoxCall<T>: BoxHo<Arg<T>> {
+    /// Calls function with some value.
+    fn call_box(f: &Self::Fun, val: T) -> Self;
+}
+
+impl<T, U> BoxCall<T> for U
+where U: BoxHo<Arg<T>, Fun = FuncBox<T, Self>> {
+    fn call_box(f: &Self::Fun, val: T) -> Self {f(val)}
+}
+
+impl<T> BoxHo<()> for T {type Fun = T;}
+
+impl<T, V: HoLeaf> BoxHo<Arg<T>> for V {type Fun = FuncBox<T, V>;}
+
+/// Function type for higher order values that stay on one thread.
+///
+/// This allows closures that capture non-thread-safe data, such as `Rc`.
+/// The function type is neither `Send` nor `Sync`.
+pub type FuncLocal<T, U> = Rc<dyn Fn(T) -> U>;
+
+/// Implemented by higher order types with functions that stay on one thread.
+///
+/// This is the counterpart of `Ho` for values that are not thread-safe.
+pub trait HoLocal<T>: Sized {
+    /// The function type.
+    type Fun: Clone;
+}
+
+/// Implemented by higher order calls with functions that stay on one thread.
+pub trait CallLocal<T>: HoLocal<Arg<T>> {
+    /// Calls function with some value.
+    fn call_local(f: &Self::Fun, val: T) -> Self;
+}
+
+impl<T, U> CallLocal<T> for U
+where U: HoLocal<Arg<T>, Fun = FuncLocal<T, Self>> {
+    fn call_local(f: &Self::Fun, val: T) -> Self {f(val)}
+}
+
+impl<T: Clone> HoLocal<()> for T {type Fun = T;}
+
+impl<T, V: HoLeaf> HoLocal<Arg<T>> for V {type Fun = FuncLocal<T, V>;}
+
+// Vectors are higher order values for primitive arguments,
+// which allows mapping e.g. `[f64; 3]` into `[Vec<f64>; 3]`.
+impl<U> Ho<Arg<f64>> for Vec<U> {type Fun = Func<f64, Vec<U>>;}
+impl<U> Ho<Arg<f32>> for Vec<U> {type Fun = Func<f32, Vec<U>>;}
+impl<U> Ho<Arg<u8>> for Vec<U> {type Fun = Func<u8, Vec<U>>;}
+impl<U> Ho<Arg<u16>> for Vec<U> {type Fun = Func<u16, Vec<U>>;}
+impl<U> Ho<Arg<u32>> for Vec<U> {type Fun = Func<u32, Vec<U>>;}
+impl<U> Ho<Arg<u64>> for Vec<U> {type Fun = Func<u64, Vec<U>>;}
+impl<U> Ho<Arg<usize>> for Vec<U> {type Fun = Func<usize, Vec<U>>;}
+impl<U> Ho<Arg<i8>> for Vec<U> {type Fun = Func<i8, Vec<U>>;}
+impl<U> Ho<Arg<i16>> for Vec<U> {type Fun = Func<i16, Vec<U>>;}
+impl<U> Ho<Arg<i32>> for Vec<U> {type Fun = Func<i32, Vec<U>>;}
+impl<U> Ho<Arg<i64>> for Vec<U> {type Fun = Func<i64, Vec<U>>;}
+impl<U> Ho<Arg<isize>> for Vec<U> {type Fun = Func<isize, Vec<U>>;}
 
 /// Higher order pairing.
 ///
@@ -268,8 +534,30 @@ pub trait HPair {
     type Out;
     /// Returns the higher order transposed value.
     fn hpair(self) -> Self::Out;
+    /// Returns the higher order transposed value,
+    /// or an error if vectors of different lengths are paired up.
+    fn try_hpair(self) -> Result<Self::Out, HPairLenError> where Self: Sized {
+        Ok(self.hpair())
+    }
 }
 
+/// Error when pairing up vectors of different lengths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HPairLenError {
+    /// The length of the left vector.
+    pub left: usize,
+    /// The length of the right vector.
+    pub right: usize,
+}
+
+impl core::fmt::Display for HPairLenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "cannot pair up vectors of lengths {} and {}", self.left, self.right)
+    }
+}
+
+impl core::error::Error for HPairLenError {}
+
 impl HPair for (f64, f64) {type Out = Self; fn hpair(self) -> Self {self}}
 impl HPair for (f32, f32) {type Out = Self; fn hpair(self) -> Self {self}}
 impl HPair for (u8, u8) {type Out = Self; fn hpair(self) -> Self {self}}
@@ -283,73 +571,131 @@ impl HPair for (i32, i32) {type Out = Self; fn hpair(self) -> Self {self}}
 impl HPair for (i64, i64) {type Out = Self; fn hpair(self) -> Self {self}}
 impl HPair for (isize, isize) {type Out = Self; fn hpair(self) -> Self {self}}
 
-impl<T> HPair for ([T; 2], [T; 2]) where (T, T): HPair {
-    type Out = [<(T, T) as HPair>::Out; 2];
+impl<A, B> HPair for ((A, B), (A, B)) where (A, A): HPair, (B, B): HPair {
+    type Out = (<(A, A) as HPair>::Out, <(B, B) as HPair>::Out);
     fn hpair(self) -> Self::Out {
-        let ([a, b], [c, d]) = self;
-        [(a, c).hpair(), (b, d).hpair()]
+        let ((a, b), (c, d)) = self;
+        ((a, c).hpair(), (b, d).hpair())
+    }
+    fn try_hpair(self) -> Result<Self::Out, HPairLenError> {
+        let ((a, b), (c, d)) = self;
+        Ok(((a, c).try_hpair()?, (b, d).try_hpair()?))
     }
 }
 
-impl<T> HPair for ([T; 3], [T; 3]) where (T, T): HPair {
-    type Out = [<(T, T) as HPair>::Out; 3];
+impl<T, const N: usize> HPair for ([T; N], [T; N]) where (T, T): HPair {
+    type Out = [<(T, T) as HPair>::Out; N];
     fn hpair(self) -> Self::Out {
-        let ([a, b, c], [d, e, f]) = self;
-        [(a, d).hpair(), (b, e).hpair(), (c, f).hpair()]
+        let (a, b) = self;
+        let mut b = IntoIterator::into_iter(b);
+        a.map(|n| (n, b.next().unwrap()).hpair())
+    }
+    fn try_hpair(self) -> Result<Self::Out, HPairLenError> {
+        let (a, b) = self;
+        let out = IntoIterator::into_iter(a).zip(b)
+            .map(|n| n.try_hpair())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(out.try_into().unwrap_or_else(|_| unreachable!()))
     }
 }
 
-impl<T> HPair for ([T; 4], [T; 4]) where (T, T): HPair {
-    type Out = [<(T, T) as HPair>::Out; 4];
+impl<T> HPair for (Vec<T>, Vec<T>) where (T, T): HPair {
+    type Out = Vec<<(T, T) as HPair>::Out>;
     fn hpair(self) -> Self::Out {
-        let ([a, b, c, d], [e, f, g, h]) = self;
-        [(a, e).hpair(), (b, f).hpair(), (c, g).hpair(), (d, h).hpair()]
+        let (a, b) = self;
+        a.into_iter().zip(b).map(|n| n.hpair()).collect()
+    }
+    fn try_hpair(self) -> Result<Self::Out, HPairLenError> {
+        let (a, b) = self;
+        if a.len() != b.len() {
+            return Err(HPairLenError {left: a.len(), right: b.len()});
+        }
+        a.into_iter().zip(b).map(|n| n.try_hpair()).collect()
     }
 }
 
-impl<T> HPair for ([T; 5], [T; 5]) where (T, T): HPair {
-    type Out = [<(T, T) as HPair>::Out; 5];
-    fn hpair(self) -> Self::Out {
-        let ([a, b, c, d, e], [f, g, h, i, j]) = self;
-        [
-            (a, f).hpair(),
-            (b, g).hpair(),
-            (c, h).hpair(),
-            (d, i).hpair(),
-            (e, j).hpair()
-        ]
+/// Higher order tripling.
+///
+/// A higher order tripling is used to line up components of three data structures.
+/// This is used before ternary higher order maps of the type `f : (T, T, T) -> U`.
+pub trait HTriple {
+    /// Output type.
+    type Out;
+    /// Returns the higher order transposed value.
+    fn htriple(self) -> Self::Out;
+}
+
+impl HTriple for (f64, f64, f64) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (f32, f32, f32) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (u8, u8, u8) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (u16, u16, u16) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (u32, u32, u32) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (u64, u64, u64) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (usize, usize, usize) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (i8, i8, i8) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (i16, i16, i16) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (i32, i32, i32) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (i64, i64, i64) {type Out = Self; fn htriple(self) -> Self {self}}
+impl HTriple for (isize, isize, isize) {type Out = Self; fn htriple(self) -> Self {self}}
+
+impl<T, const N: usize> HTriple for ([T; N], [T; N], [T; N]) where (T, T, T): HTriple {
+    type Out = [<(T, T, T) as HTriple>::Out; N];
+    fn htriple(self) -> Self::Out {
+        let (a, b, c) = self;
+        let mut b = IntoIterator::into_iter(b);
+        let mut c = IntoIterator::into_iter(c);
+        a.map(|n| (n, b.next().unwrap(), c.next().unwrap()).htriple())
     }
 }
 
-impl<T> HPair for ([T; 6], [T; 6]) where (T, T): HPair {
-    type Out = [<(T, T) as HPair>::Out; 6];
-    fn hpair(self) -> Self::Out {
-        let ([a, b, c, d, e, f], [g, h, i, j, k, l]) = self;
-        [
-            (a, g).hpair(),
-            (b, h).hpair(),
-            (c, i).hpair(),
-            (d, j).hpair(),
-            (e, k).hpair(),
-            (f, l).hpair()
-        ]
+impl<T> HTriple for (Vec<T>, Vec<T>, Vec<T>) where (T, T, T): HTriple {
+    type Out = Vec<<(T, T, T) as HTriple>::Out>;
+    fn htriple(self) -> Self::Out {
+        let (a, b, c) = self;
+        a.into_iter().zip(b).zip(c).map(|((a, b), c)| (a, b, c).htriple()).collect()
     }
 }
 
-impl<T> HPair for (Vec<T>, Vec<T>) where (T, T): HPair {
-    type Out = Vec<<(T, T) as HPair>::Out>;
-    fn hpair(self) -> Self::Out {
-        let (a, b) = self;
-        a.into_iter().zip(b.into_iter()).map(|n| n.hpair()).collect()
+/// Higher order zipping.
+///
+/// Pairs up two structures and maps the pairs with a binary function.
+/// This is the same as `(a, b).hpair().hmap(f)`.
+///
+/// Implemented for the types that `HPair` pairs up: primitives, pairs, arrays and vectors.
+pub trait HZip<Rhs = Self>: Sized {
+    /// Pairs up `self` with `other` and maps the pairs.
+    fn hzip<O>(self, other: Rhs, f: &<<(Self, Rhs) as HPair>::Out as HMap<O>>::Fun) -> O
+        where (Self, Rhs): HPair, <(Self, Rhs) as HPair>::Out: HMap<O>
+    {
+        (self, other).hpair().hmap(f)
     }
 }
 
+impl HZip for f64 {}
+impl HZip for f32 {}
+impl HZip for u8 {}
+impl HZip for u16 {}
+impl HZip for u32 {}
+impl HZip for u64 {}
+impl HZip for usize {}
+impl HZip for i8 {}
+impl HZip for i16 {}
+impl HZip for i32 {}
+impl HZip for i64 {}
+impl HZip for isize {}
+impl<A, B> HZip for (A, B) {}
+impl<T, const N: usize> HZip for [T; N] {}
+impl<T> HZip for Vec<T> {}
+
 /// Implemented by higher order maps.
 ///
 /// A higher order map takes common data structures such as
 /// vectors and lists and applies a function to every element.
 ///
 /// This is implemented recursively, hence higher order maps.
+///
+/// Arrays can be mapped both owned and borrowed.
+/// A borrowed array `&[T; N]` clones each element before mapping it.
 pub trait HMap<Out> {
     /// The out type.
     type Fun;
@@ -365,55 +711,257 @@ where U: Call<T> {
     }
 }
 
-impl<T, U> HMap<[U; 2]> for [T; 2]
+impl<T, U, const N: usize> HMap<[U; N]> for [T; N]
 where T: HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, f: &Self::Fun) -> [U; 2] {
-        let [a, b] = self;
-        [a.hmap(f), b.hmap(f)]
+    fn hmap(self, f: &Self::Fun) -> [U; N] {
+        self.map(|n| n.hmap(f))
     }
 }
 
-impl<T, U> HMap<[U; 3]> for [T; 3]
-where T: HMap<U> {
+impl<T, U, const N: usize> HMap<[U; N]> for &[T; N]
+where T: Clone + HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, f: &Self::Fun) -> [U; 3] {
-        let [a, b, c] = self;
-        [a.hmap(f), b.hmap(f), c.hmap(f)]
+    fn hmap(self, f: &Self::Fun) -> [U; N] {
+        core::array::from_fn(|i| self[i].clone().hmap(f))
     }
 }
 
-impl<T, U> HMap<[U; 4]> for [T; 4]
+impl<T, U> HMap<Vec<U>> for Vec<T>
 where T: HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, f: &Self::Fun) -> [U; 4] {
-        let [a, b, c, d] = self;
-        [a.hmap(f), b.hmap(f), c.hmap(f), d.hmap(f)]
+    fn hmap(self, f: &Self::Fun) -> Vec<U> {
+        self.into_iter().map(|n| n.hmap(f)).collect()
     }
 }
 
-impl<T, U> HMap<[U; 5]> for [T; 5]
+// `Box` is a fundamental type, so a generic `Box<T>` impl would overlap with the scalar impl.
+// Boxed arrays and slices are supported instead.
+impl<T, U, const N: usize> HMap<Box<[U; N]>> for Box<[T; N]>
 where T: HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, f: &Self::Fun) -> [U; 5] {
-        let [a, b, c, d, e] = self;
-        [a.hmap(f), b.hmap(f), c.hmap(f), d.hmap(f), e.hmap(f)]
+    fn hmap(self, f: &Self::Fun) -> Box<[U; N]> {
+        Box::new((*self).hmap(f))
     }
 }
 
-impl<T, U> HMap<[U; 6]> for [T; 6]
+impl<T, U> HMap<Box<[U]>> for Box<[T]>
 where T: HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, fx: &Self::Fun) -> [U; 6] {
-        let [a, b, c, d, e, f] = self;
-        [a.hmap(fx), b.hmap(fx), c.hmap(fx), d.hmap(fx), e.hmap(fx), f.hmap(fx)]
+    fn hmap(self, f: &Self::Fun) -> Box<[U]> {
+        self.into_vec().into_iter().map(|n| n.hmap(f)).collect()
     }
 }
 
-impl<T, U> HMap<Vec<U>> for Vec<T>
+impl<T, U> HMap<VecDeque<U>> for VecDeque<T>
 where T: HMap<U> {
     type Fun = T::Fun;
-    fn hmap(self, f: &Self::Fun) -> Vec<U> {
+    fn hmap(self, f: &Self::Fun) -> VecDeque<U> {
         self.into_iter().map(|n| n.hmap(f)).collect()
     }
 }
+
+impl<K, T, U> HMap<BTreeMap<K, U>> for BTreeMap<K, T>
+where K: Ord, T: HMap<U> {
+    type Fun = T::Fun;
+    fn hmap(self, f: &Self::Fun) -> BTreeMap<K, U> {
+        self.into_iter().map(|(k, n)| (k, n.hmap(f))).collect()
+    }
+}
+
+impl<A, B, X, Y> HMap<(X, Y)> for (A, B)
+where A: HMap<X>, B: HMap<Y> {
+    type Fun = (A::Fun, B::Fun);
+    fn hmap(self, f: &Self::Fun) -> (X, Y) {
+        let (a, b) = self;
+        (a.hmap(&f.0), b.hmap(&f.1))
+    }
+}
+
+impl<T, U> HMap<Option<U>> for Option<T>
+where T: HMap<U> {
+    type Fun = T::Fun;
+    fn hmap(self, f: &Self::Fun) -> Option<U> {
+        self.map(|n| n.hmap(f))
+    }
+}
+
+impl<T, U, E> HMap<Result<U, E>> for Result<T, E>
+where T: HMap<U> {
+    type Fun = T::Fun;
+    fn hmap(self, f: &Self::Fun) -> Result<U, E> {
+        self.map(|n| n.hmap(f))
+    }
+}
+
+/// Implemented by fallible higher order maps.
+///
+/// A fallible higher order map stops at the first element that fails.
+/// Elements are counted by a flat index in the order they are visited,
+/// such that the failing element can be located in nested structures.
+pub trait TryHMap<Out, E>: Sized {
+    /// The function type.
+    type Fun;
+    /// Maps structure, counting elements from `index`.
+    fn try_hmap_at(self, f: &Self::Fun, index: &mut usize) -> Result<Out, (usize, E)>;
+    /// Maps structure, returning the first error.
+    fn try_hmap(self, f: &Self::Fun) -> Result<Out, E> {
+        self.try_hmap_indexed(f).map_err(|(_, e)| e)
+    }
+    /// Maps structure, returning the first error with the flat index of the failing element.
+    fn try_hmap_indexed(self, f: &Self::Fun) -> Result<Out, (usize, E)> {
+        self.try_hmap_at(f, &mut 0)
+    }
+}
+
+impl<T, U, E> TryHMap<U, E> for T
+where U: Call<T> {
+    type Fun = Func<T, Result<U, E>>;
+    fn try_hmap_at(self, f: &Self::Fun, index: &mut usize) -> Result<U, (usize, E)> {
+        let i = *index;
+        *index += 1;
+        f(self).map_err(|e| (i, e))
+    }
+}
+
+impl<T, U, E, const N: usize> TryHMap<[U; N], E> for [T; N]
+where T: TryHMap<U, E> {
+    type Fun = T::Fun;
+    fn try_hmap_at(self, f: &Self::Fun, index: &mut usize) -> Result<[U; N], (usize, E)> {
+        let mut out = Vec::with_capacity(N);
+        for n in self {
+            out.push(n.try_hmap_at(f, index)?);
+        }
+        Ok(out.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+impl<T, U, E> TryHMap<Vec<U>, E> for Vec<T>
+where T: TryHMap<U, E> {
+    type Fun = T::Fun;
+    fn try_hmap_at(self, f: &Self::Fun, index: &mut usize) -> Result<Vec<U>, (usize, E)> {
+        self.into_iter().map(|n| n.try_hmap_at(f, index)).collect()
+    }
+}
+
+/// Implemented by higher order maps that borrow the structure.
+///
+/// Elements are cloned before mapping, which leaves the original structure intact.
+pub trait HMapRef<Out> {
+    /// The function type.
+    type Fun;
+    /// Maps structure by reference.
+    fn hmap_ref(&self, f: &Self::Fun) -> Out;
+}
+
+impl<T, U> HMapRef<U> for T
+where T: Clone, U: Call<T> {
+    type Fun = U::Fun;
+    fn hmap_ref(&self, f: &Self::Fun) -> U {
+        <U as Call<T>>::call(f, self.clone())
+    }
+}
+
+impl<T, U, const N: usize> HMapRef<[U; N]> for [T; N]
+where T: HMapRef<U> {
+    type Fun = T::Fun;
+    fn hmap_ref(&self, f: &Self::Fun) -> [U; N] {
+        core::array::from_fn(|i| self[i].hmap_ref(f))
+    }
+}
+
+impl<T, U> HMapRef<Vec<U>> for Vec<T>
+where T: HMapRef<U> {
+    type Fun = T::Fun;
+    fn hmap_ref(&self, f: &Self::Fun) -> Vec<U> {
+        self.iter().map(|n| n.hmap_ref(f)).collect()
+    }
+}
+
+/// Implemented by higher order maps that report the shape of the structure.
+pub trait HMapMeta<Out> {
+    /// The function type.
+    type Fun;
+    /// Maps structure, returning `(output, count, depth)`.
+    ///
+    /// The count is the number of scalar elements mapped.
+    /// The depth is the maximum nesting depth, where a scalar has depth `0`.
+    fn hmap_meta(self, f: &Self::Fun) -> (Out, usize, usize);
+}
+
+impl<T, U> HMapMeta<U> for T
+where U: Call<T> {
+    type Fun = U::Fun;
+    fn hmap_meta(self, f: &Self::Fun) -> (U, usize, usize) {
+        (<U as Call<T>>::call(f, self), 1, 0)
+    }
+}
+
+impl<T, U, const N: usize> HMapMeta<[U; N]> for [T; N]
+where T: HMapMeta<U> {
+    type Fun = T::Fun;
+    fn hmap_meta(self, f: &Self::Fun) -> ([U; N], usize, usize) {
+        let (mut count, mut depth) = (0, 0);
+        let out = self.map(|n| {
+            let (u, c, d) = n.hmap_meta(f);
+            count += c;
+            depth = depth.max(d);
+            u
+        });
+        (out, count, depth + 1)
+    }
+}
+
+impl<T, U> HMapMeta<Vec<U>> for Vec<T>
+where T: HMapMeta<U> {
+    type Fun = T::Fun;
+    fn hmap_meta(self, f: &Self::Fun) -> (Vec<U>, usize, usize) {
+        let (mut count, mut depth) = (0, 0);
+        let out = self.into_iter().map(|n| {
+            let (u, c, d) = n.hmap_meta(f);
+            count += c;
+            depth = depth.max(d);
+            u
+        }).collect();
+        (out, count, depth + 1)
+    }
+}
+
+/// Implemented by higher order folds.
+///
+/// A higher order fold reduces the elements of a structure from left to right.
+/// This is commonly used after `HMap::hmap`, e.g. to compute a sum or centroid.
+pub trait HFold {
+    /// The element type.
+    type Item;
+    /// Folds elements from left to right.
+    fn hfold<A, F>(self, init: A, f: F) -> A
+        where F: FnMut(A, Self::Item) -> A;
+}
+
+impl<T> HFold for Vec<T> {
+    type Item = T;
+    fn hfold<A, F>(self, init: A, f: F) -> A
+        where F: FnMut(A, T) -> A
+    {
+        self.into_iter().fold(init, f)
+    }
+}
+
+impl<T, const N: usize> HFold for [T; N] {
+    type Item = T;
+    fn hfold<A, F>(self, init: A, f: F) -> A
+        where F: FnMut(A, T) -> A
+    {
+        IntoIterator::into_iter(self).fold(init, f)
+    }
+}
+
+impl<T> HFold for Option<T> {
+    type Item = T;
+    fn hfold<A, F>(self, init: A, f: F) -> A
+        where F: FnMut(A, T) -> A
+    {
+        self.into_iter().fold(init, f)
+    }
+}
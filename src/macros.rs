@@ -0,0 +1,93 @@
+//! Macros for higher order operators.
+
+/// Implements an operator for both the ordinary and the higher order case.
+///
+/// The ordinary case is written once, e.g. for `Point`.
+/// The higher order case, e.g. for `PointFunc<T>`, calls the values at the argument
+/// and applies the ordinary case, returning a `Func<T, Output>`.
+/// This requires `Call<T>` for the ordinary type.
+///
+/// Unary operators take `self`, and binary operators take `self` and another `Self`:
+///
+/// ```rust
+/// use ha::{Arg, Call, Fun, Func, Ho};
+/// use std::sync::Arc;
+///
+/// # #[derive(Clone)]
+/// # pub struct Point<T = ()> where f64: Ho<T> {
+/// #     pub x: Fun<T, f64>,
+/// #     pub y: Fun<T, f64>,
+/// # }
+/// # pub type PointFunc<T> = Point<Arg<T>>;
+/// # impl<T: Clone> Ho<Arg<T>> for Point {
+/// #     type Fun = PointFunc<T>;
+/// # }
+/// # impl<T: Copy> Call<T> for Point where f64: Ho<Arg<T>> + Call<T> {
+/// #     fn call(f: &Self::Fun, val: T) -> Point {
+/// #         Point::<()> {x: <f64 as Call<T>>::call(&f.x, val), y: <f64 as Call<T>>::call(&f.y, val)}
+/// #     }
+/// # }
+/// /// Dot operator.
+/// pub trait Dot<Rhs = Self> {
+///     /// The output type.
+///     type Output;
+///     /// Returns the dot product.
+///     fn dot(self, other: Rhs) -> Self::Output;
+/// }
+///
+/// ha::higher_order_op! {
+///     impl Dot for Point, PointFunc {
+///         type Output = f64;
+///         fn dot(self, other: Self) -> f64 {
+///             self.x * other.x + self.y * other.y
+///         }
+///     }
+/// }
+///
+/// let a: PointFunc<f64> = Point {x: Arc::new(|t| t), y: Arc::new(|_| 1.0)};
+/// let b: PointFunc<f64> = Point {x: Arc::new(|_| 2.0), y: Arc::new(|t| -t)};
+/// let f: Func<f64, f64> = a.dot(b);
+/// assert_eq!(f(3.0), 3.0);
+/// ```
+#[macro_export]
+macro_rules! higher_order_op {
+    (impl $tr:ident for $ty:ident, $fun:ident {
+        type Output = $out:ty;
+        fn $f:ident($s:tt, $o:ident: Self) -> $ret:ty $body:block
+    }) => {
+        impl $tr for $ty {
+            type Output = $out;
+            fn $f($s, $o: Self) -> $ret $body
+        }
+
+        impl<T: 'static + Copy> $tr for $fun<T>
+            where $ty: $crate::Call<T> + $crate::Ho<$crate::Arg<T>, Fun = $fun<T>>
+        {
+            type Output = $crate::Func<T, $out>;
+            fn $f(self, other: Self) -> $crate::Func<T, $out> {
+                $crate::func(move |a: T| <$ty as $tr>::$f(
+                    <$ty as $crate::Call<T>>::call(&self, a),
+                    <$ty as $crate::Call<T>>::call(&other, a),
+                ))
+            }
+        }
+    };
+    (impl $tr:ident for $ty:ident, $fun:ident {
+        type Output = $out:ty;
+        fn $f:ident($s:tt) -> $ret:ty $body:block
+    }) => {
+        impl $tr for $ty {
+            type Output = $out;
+            fn $f($s) -> $ret $body
+        }
+
+        impl<T: 'static + Copy> $tr for $fun<T>
+            where $ty: $crate::Call<T> + $crate::Ho<$crate::Arg<T>, Fun = $fun<T>>
+        {
+            type Output = $crate::Func<T, $out>;
+            fn $f(self) -> $crate::Func<T, $out> {
+                $crate::func(move |a: T| <$ty as $tr>::$f(<$ty as $crate::Call<T>>::call(&self, a)))
+            }
+        }
+    };
+}
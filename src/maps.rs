@@ -0,0 +1,169 @@
+//! Higher order maps with extra behavior.
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Arg, Call, HMap, Ho};
+
+/// Maps a vector and removes consecutive duplicate outputs.
+///
+/// This is useful for run-length style compression of generated output.
+pub fn hmap_dedup<T, U>(data: Vec<T>, f: &<T as HMap<U>>::Fun) -> Vec<U>
+    where T: HMap<U>, U: PartialEq
+{
+    let mut out: Vec<U> = data.into_iter().map(|n| n.hmap(f)).collect();
+    out.dedup();
+    out
+}
+
+/// Maps a jagged grid with a function of row, column and value.
+///
+/// Rows may have different lengths. Empty rows stay empty in the output.
+pub fn hmap_grid<T, U>(
+    grid: Vec<Vec<T>>,
+    f: &<U as Ho<Arg<(usize, usize, T)>>>::Fun
+) -> Vec<Vec<U>>
+    where U: Call<(usize, usize, T)>
+{
+    grid.into_iter().enumerate().map(|(i, row)| {
+        row.into_iter().enumerate().map(|(j, n)| U::call(f, (i, j, n))).collect()
+    }).collect()
+}
+
+/// Maps a vector while threading an accumulator through the elements.
+///
+/// Returns the final state together with the mapped outputs.
+pub fn hmap_accum<T, S, U>(
+    data: Vec<T>,
+    init: S,
+    mut f: impl FnMut(S, T) -> (S, U)
+) -> (S, Vec<U>) {
+    let mut state = init;
+    let mut out = Vec::with_capacity(data.len());
+    for n in data {
+        let (s, u) = f(state, n);
+        state = s;
+        out.push(u);
+    }
+    (state, out)
+}
+
+/// Maps the values of a vector of `(priority, value)` pairs.
+///
+/// Priorities and order are left unchanged.
+pub fn hmap_prioritized<P, T, U>(data: Vec<(P, T)>, f: &<T as HMap<U>>::Fun) -> Vec<(P, U)>
+    where T: HMap<U>
+{
+    data.into_iter().map(|(p, n)| (p, n.hmap(f))).collect()
+}
+
+/// Maps a nested vector and flattens the result into a single vector.
+///
+/// The nesting structure of the input is lost.
+pub fn hmap_flat<T, U>(data: Vec<Vec<T>>, f: &<T as HMap<U>>::Fun) -> Vec<U>
+    where T: HMap<U>
+{
+    data.into_iter().flatten().map(|n| n.hmap(f)).collect()
+}
+
+/// Interleaves the elements of two vectors.
+///
+/// Returns `a[0], b[0], a[1], b[1], ...`.
+/// When one vector is longer, its remaining elements are appended at the end.
+pub fn interleave<T>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+    loop {
+        match (a.next(), b.next()) {
+            (Some(x), Some(y)) => {out.push(x); out.push(y)}
+            (Some(x), None) => {out.push(x); out.extend(a); break}
+            (None, Some(y)) => {out.push(y); out.extend(b); break}
+            (None, None) => break,
+        }
+    }
+    out
+}
+
+/// Maps three parallel vectors in struct-of-arrays layout.
+///
+/// Each vector is mapped by its own function, e.g. `x`, `y` and `z` coordinates.
+/// Returns `None` if the vectors do not have equal lengths.
+pub fn hmap_soa<T, U>(
+    data: [Vec<T>; 3],
+    fs: [&<T as HMap<U>>::Fun; 3]
+) -> Option<[Vec<U>; 3]>
+    where T: HMap<U>
+{
+    let [xs, ys, zs] = data;
+    if xs.len() != ys.len() || xs.len() != zs.len() {return None};
+    let n = xs.len();
+    let mut out = [Vec::with_capacity(n), Vec::with_capacity(n), Vec::with_capacity(n)];
+    for ((x, y), z) in xs.into_iter().zip(ys).zip(zs) {
+        out[0].push(x.hmap(fs[0]));
+        out[1].push(y.hmap(fs[1]));
+        out[2].push(z.hmap(fs[2]));
+    }
+    Some(out)
+}
+
+/// Maps elements lazily, without allocating an output vector.
+///
+/// The function handle is cloned into the returned iterator,
+/// which calls it only for the elements that are pulled.
+/// Slices can be mapped with e.g. `hmap_iter(slice.iter().cloned(), &f)`.
+pub fn hmap_iter<I, T, U>(data: I, f: &<T as HMap<U>>::Fun) -> impl Iterator<Item = U>
+    where I: IntoIterator<Item = T>, T: HMap<U>, <T as HMap<U>>::Fun: Clone
+{
+    let f = f.clone();
+    data.into_iter().map(move |n| n.hmap(&f))
+}
+
+/// Maps elements lazily, yielding each output together with its index.
+///
+/// Like `hmap_iter`, the function is called only for the elements that are pulled.
+pub fn hmap_enumerate_iter<I, T, U>(
+    data: I,
+    f: &<T as HMap<U>>::Fun
+) -> impl Iterator<Item = (usize, U)>
+    where I: IntoIterator<Item = T>, T: HMap<U>, <T as HMap<U>>::Fun: Clone
+{
+    hmap_iter(data, f).enumerate()
+}
+
+/// Maps a vector and also returns a lookup table from inputs to outputs.
+///
+/// Duplicate inputs collapse into a single entry of the table,
+/// keeping the output of the last occurrence.
+#[cfg(feature = "std")]
+pub fn hmap_to_lookup<T, U>(data: Vec<T>, f: &<T as HMap<U>>::Fun) -> (Vec<U>, HashMap<T, U>)
+    where T: HMap<U> + Eq + Hash + Clone, U: Clone
+{
+    let mut lookup = HashMap::with_capacity(data.len());
+    let out = data.into_iter().map(|n| {
+        let u = n.clone().hmap(f);
+        lookup.insert(n, u.clone());
+        u
+    }).collect();
+    (out, lookup)
+}
+
+/// Maps a vector, calling the function once per distinct input.
+///
+/// Repeated inputs reuse the output of the first occurrence.
+/// This is useful when the function is expensive and inputs repeat often.
+#[cfg(feature = "std")]
+pub fn hmap_dedup_input<T, U>(data: Vec<T>, f: &<T as HMap<U>>::Fun) -> Vec<U>
+    where T: HMap<U> + Eq + Hash + Clone, U: Clone
+{
+    let mut cache: HashMap<T, U> = HashMap::new();
+    data.into_iter().map(|n| {
+        if let Some(u) = cache.get(&n) {return u.clone()};
+        let u = n.clone().hmap(f);
+        cache.insert(n, u.clone());
+        u
+    }).collect()
+}
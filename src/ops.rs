@@ -0,0 +1,235 @@
+//! Operations on ordinary and higher order values.
+
+use alloc::sync::Arc;
+
+use crate::{derivative, Func};
+
+/// Length operator.
+pub trait Length {
+    /// The output type.
+    type Output;
+
+    /// Returns the length.
+    fn length(self) -> Self::Output;
+}
+
+#[cfg(feature = "std")]
+impl Length for [f64; 2] {
+    type Output = f64;
+    fn length(self) -> f64 {
+        let [x, y] = self;
+        (x * x + y * y).sqrt()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Length for [f64; 3] {
+    type Output = f64;
+    fn length(self) -> f64 {
+        let [x, y, z] = self;
+        (x * x + y * y + z * z).sqrt()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Length for [f64; 4] {
+    type Output = f64;
+    fn length(self) -> f64 {
+        let [x, y, z, w] = self;
+        (x * x + y * y + z * z + w * w).sqrt()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: 'static + Copy> Length for [Func<T, f64>; 2] {
+    type Output = Func<T, f64>;
+    fn length(self) -> Func<T, f64> {
+        let [x, y] = self;
+        Arc::new(move |a| [x(a), y(a)].length())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: 'static + Copy> Length for [Func<T, f64>; 3] {
+    type Output = Func<T, f64>;
+    fn length(self) -> Func<T, f64> {
+        let [x, y, z] = self;
+        Arc::new(move |a| [x(a), y(a), z(a)].length())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: 'static + Copy> Length for [Func<T, f64>; 4] {
+    type Output = Func<T, f64>;
+    fn length(self) -> Func<T, f64> {
+        let [x, y, z, w] = self;
+        Arc::new(move |a| [x(a), y(a), z(a), w(a)].length())
+    }
+}
+
+/// Reflection about a plane through the origin.
+pub trait ReflectPlane {
+    /// The output type.
+    type Output;
+
+    /// Returns the reflection about the plane with unit normal `normal`.
+    fn reflect_plane(self, normal: [f64; 3]) -> Self::Output;
+}
+
+impl ReflectPlane for [f64; 3] {
+    type Output = [f64; 3];
+    fn reflect_plane(self, n: [f64; 3]) -> [f64; 3] {
+        let [x, y, z] = self;
+        let d = 2.0 * (x * n[0] + y * n[1] + z * n[2]);
+        [x - d * n[0], y - d * n[1], z - d * n[2]]
+    }
+}
+
+impl<T: 'static + Copy> ReflectPlane for [Func<T, f64>; 3] {
+    type Output = [Func<T, f64>; 3];
+    fn reflect_plane(self, n: [f64; 3]) -> [Func<T, f64>; 3] {
+        let [x, y, z] = self;
+        let component = |i: usize| -> Func<T, f64> {
+            let (x, y, z) = (x.clone(), y.clone(), z.clone());
+            Arc::new(move |a| [x(a), y(a), z(a)].reflect_plane(n)[i])
+        };
+        [component(0), component(1), component(2)]
+    }
+}
+
+/// Blending of higher order values by a higher order factor.
+pub trait HMixFun<T> {
+    /// Returns the blend of `self` and `other` using factor `t`.
+    ///
+    /// A factor of `0` gives `self` and a factor of `1` gives `other`.
+    fn hmix_fun(self, other: Self, t: Func<T, f64>) -> Self;
+}
+
+impl<T: 'static + Clone> HMixFun<T> for Func<T, f64> {
+    fn hmix_fun(self, other: Self, t: Func<T, f64>) -> Self {
+        Arc::new(move |a: T| {
+            let s = t(a.clone());
+            let x = self(a.clone());
+            x + (other(a) - x) * s
+        })
+    }
+}
+
+impl<T, X, const N: usize> HMixFun<T> for [X; N]
+    where X: HMixFun<T>
+{
+    fn hmix_fun(self, other: Self, t: Func<T, f64>) -> Self {
+        let mut other = IntoIterator::into_iter(other);
+        self.map(|a| a.hmix_fun(other.next().unwrap(), t.clone()))
+    }
+}
+
+/// Field-wise accumulation of ordinary values.
+pub trait HAccumulate {
+    /// Returns the field-wise sum.
+    fn hadd(self, other: Self) -> Self;
+    /// Returns the value with every field scaled.
+    fn hscale(self, s: f64) -> Self;
+}
+
+impl HAccumulate for f64 {
+    fn hadd(self, other: Self) -> Self {self + other}
+    fn hscale(self, s: f64) -> Self {self * s}
+}
+
+impl HAccumulate for f32 {
+    fn hadd(self, other: Self) -> Self {self + other}
+    fn hscale(self, s: f64) -> Self {self * s as f32}
+}
+
+impl<X, const N: usize> HAccumulate for [X; N]
+    where X: HAccumulate
+{
+    fn hadd(self, other: Self) -> Self {
+        let mut other = IntoIterator::into_iter(other);
+        self.map(|a| a.hadd(other.next().unwrap()))
+    }
+    fn hscale(self, s: f64) -> Self {
+        self.map(|a| a.hscale(s))
+    }
+}
+
+/// Constructive solid geometry on signed distance functions.
+pub trait CombineSdf {
+    /// Returns the union, which is inside when either shape is inside.
+    fn union(self, other: Self) -> Self;
+    /// Returns the intersection, which is inside when both shapes are inside.
+    fn intersect(self, other: Self) -> Self;
+    /// Returns the shape with `other` cut away.
+    fn subtract(self, other: Self) -> Self;
+    /// Returns the union blended smoothly over distance `k`.
+    ///
+    /// With `k <= 0`, there is no blending and this is the same as `union`.
+    fn smooth_union(self, other: Self, k: f64) -> Self;
+}
+
+impl CombineSdf for Func<[f64; 3], f64> {
+    fn union(self, other: Self) -> Self {
+        Arc::new(move |p| self(p).min(other(p)))
+    }
+    fn intersect(self, other: Self) -> Self {
+        Arc::new(move |p| self(p).max(other(p)))
+    }
+    fn subtract(self, other: Self) -> Self {
+        Arc::new(move |p| self(p).max(-other(p)))
+    }
+    fn smooth_union(self, other: Self, k: f64) -> Self {
+        Arc::new(move |p| {
+            let (a, b) = (self(p), other(p));
+            if k <= 0.0 {return a.min(b)};
+            let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+            b + (a - b) * h - k * h * (1.0 - h)
+        })
+    }
+}
+
+/// Pointwise difference of ordinary and higher order values.
+pub trait HDiff {
+    /// Returns the difference `self - other`, field by field.
+    fn hdiff(self, other: Self) -> Self;
+}
+
+impl HDiff for f64 {
+    fn hdiff(self, other: Self) -> Self {self - other}
+}
+
+impl<T: 'static + Clone> HDiff for Func<T, f64> {
+    fn hdiff(self, other: Self) -> Self {
+        Arc::new(move |a: T| self(a.clone()) - other(a))
+    }
+}
+
+impl<X, const N: usize> HDiff for [X; N]
+    where X: HDiff
+{
+    fn hdiff(self, other: Self) -> Self {
+        let mut other = IntoIterator::into_iter(other);
+        self.map(|a| a.hdiff(other.next().unwrap()))
+    }
+}
+
+/// Numerical derivative of higher order values.
+pub trait HDerivative {
+    /// Returns the derivative, field by field,
+    /// computed by central difference with step size `h`.
+    fn hderivative(self, h: f64) -> Self;
+}
+
+impl HDerivative for Func<f64, f64> {
+    fn hderivative(self, h: f64) -> Self {
+        Arc::new(move |t| derivative(&*self, t, h))
+    }
+}
+
+impl<X, const N: usize> HDerivative for [X; N]
+    where X: HDerivative
+{
+    fn hderivative(self, h: f64) -> Self {
+        self.map(|a| a.hderivative(h))
+    }
+}
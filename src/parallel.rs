@@ -0,0 +1,90 @@
+//! Higher order maps on worker threads.
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::HMap;
+
+/// Implemented by higher order maps that run on worker threads.
+///
+/// The output is in the same order as the input, independent of scheduling.
+/// If the function panics, the panic is propagated to the caller
+/// after all workers have stopped.
+pub trait ParHMap<Out> {
+    /// The function type.
+    type Fun;
+    /// Maps structure on a pool of `threads` workers.
+    ///
+    /// Work is handed to the workers in chunks through a bounded queue,
+    /// such that at most `threads` chunks are waiting at any time.
+    fn hmap_pooled(self, f: &Self::Fun, threads: usize) -> Out;
+    /// Maps structure on one worker per available core.
+    ///
+    /// The output is equal to the sequential `hmap`, element for element.
+    fn par_hmap(self, f: &Self::Fun) -> Out;
+    /// Maps structure on one worker per available core, in chunks of `chunk_size` elements.
+    ///
+    /// Each chunk is a unit of work handed to a worker.
+    /// Cheap functions need large chunks, such that the cost of handing out work is amortized,
+    /// e.g. thousands of elements for simple arithmetic.
+    /// Expensive functions or functions with uneven cost benefit from small chunks,
+    /// such that all workers stay busy until the end.
+    fn par_hmap_chunked(self, f: &Self::Fun, chunk_size: usize) -> Out;
+}
+
+impl<T, U> ParHMap<Vec<U>> for Vec<T>
+where T: HMap<U> + Send, U: Send, T::Fun: Sync {
+    type Fun = T::Fun;
+    fn hmap_pooled(self, f: &Self::Fun, threads: usize) -> Vec<U> {
+        let threads = threads.max(1);
+        let chunk_size = self.len().div_ceil(4 * threads);
+        map_pooled(self, f, threads, chunk_size)
+    }
+    fn par_hmap(self, f: &Self::Fun) -> Vec<U> {
+        self.hmap_pooled(f, available_threads())
+    }
+    fn par_hmap_chunked(self, f: &Self::Fun, chunk_size: usize) -> Vec<U> {
+        map_pooled(self, f, available_threads(), chunk_size)
+    }
+}
+
+fn available_threads() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+fn map_pooled<T, U>(data: Vec<T>, f: &T::Fun, threads: usize, chunk_size: usize) -> Vec<U>
+    where T: HMap<U> + Send, U: Send, T::Fun: Sync
+{
+    let chunk_size = chunk_size.max(1);
+    let (task_tx, task_rx) = mpsc::sync_channel::<(usize, Vec<T>)>(threads);
+    let (out_tx, out_rx) = mpsc::channel::<(usize, Vec<U>)>();
+    // Workers share the receiver, which is dropped when the last worker stops,
+    // such that sending never blocks forever after a panic.
+    let task_rx = Arc::new(Mutex::new(task_rx));
+    thread::scope(|s| {
+        for _ in 0..threads {
+            let task_rx = task_rx.clone();
+            let out_tx = out_tx.clone();
+            s.spawn(move || loop {
+                let task = task_rx.lock().unwrap().recv();
+                match task {
+                    Ok((i, chunk)) => {
+                        let _ = out_tx.send((i, chunk.into_iter().map(|n| n.hmap(f)).collect()));
+                    }
+                    Err(_) => break,
+                }
+            });
+        }
+        drop(task_rx);
+        drop(out_tx);
+        let mut data = data.into_iter();
+        for i in 0.. {
+            let chunk: Vec<T> = data.by_ref().take(chunk_size).collect();
+            if chunk.is_empty() || task_tx.send((i, chunk)).is_err() {break}
+        }
+        drop(task_tx);
+    });
+    let mut chunks: Vec<(usize, Vec<U>)> = out_rx.into_iter().collect();
+    chunks.sort_by_key(|c| c.0);
+    chunks.into_iter().flat_map(|c| c.1).collect()
+}
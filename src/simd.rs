@@ -0,0 +1,25 @@
+//! Higher order SIMD vectors.
+//!
+//! This module requires the `simd` feature, which uses `std::simd` and needs a nightly compiler.
+//!
+//! SIMD vectors are higher order primitives, just like `f64`:
+//!
+//! ```rust
+//! #![feature(portable_simd)]
+//! use ha::{Call, Func};
+//! use std::simd::{f32x4, num::SimdFloat};
+//! use std::sync::Arc;
+//!
+//! let f: Func<f32, f32x4> = Arc::new(|t| f32x4::from_array([t, 2.0 * t, 3.0 * t, 4.0 * t]));
+//! let v = <f32x4 as Call<f32>>::call(&f, 1.0);
+//! assert_eq!(v.reduce_sum(), 10.0);
+//! ```
+
+use core::simd::{f32x4, f32x8, f64x2, f64x4};
+
+use crate::HoLeaf;
+
+impl HoLeaf for f32x4 {}
+impl HoLeaf for f32x8 {}
+impl HoLeaf for f64x2 {}
+impl HoLeaf for f64x4 {}
@@ -0,0 +1,154 @@
+//! Higher order functions with internal state.
+//!
+//! The output of these functions depends on the order in which they are called.
+//! When shared between threads, calls are serialized but their order is not specified.
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+use crate::Func;
+
+/// Creates a latching function that switches between `0` and `1`.
+///
+/// The output switches to `1` when the input rises above `high`,
+/// and back to `0` when the input falls below `low`.
+/// In between, the previous output is kept, starting at `0`.
+#[cfg(feature = "std")]
+pub fn hysteresis(low: f64, high: f64) -> Func<f64, f64> {
+    let on = Mutex::new(false);
+    Arc::new(move |t| {
+        let mut on = on.lock().unwrap();
+        if t > high {*on = true} else if t < low {*on = false};
+        if *on {1.0} else {0.0}
+    })
+}
+
+/// Limits the number of times a function is called.
+///
+/// The first `max` calls are passed through,
+/// after which every call returns `on_exceed` without calling the function.
+/// The count saturates instead of wrapping around, so the limit is permanent.
+pub fn call_limit<T, U>(f: Func<T, U>, max: usize, on_exceed: U) -> Func<T, U>
+    where T: 'static, U: Clone + Send + Sync + 'static
+{
+    let count = AtomicUsize::new(0);
+    Arc::new(move |t| {
+        let prev = count.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |c| c.checked_add(1))
+            .unwrap_or_else(|c| c);
+        if prev < max {f(t)} else {on_exceed.clone()}
+    })
+}
+
+/// Creates a function returning the running mean and variance of its inputs.
+///
+/// Each call adds the input to the statistics and returns `(mean, variance)`
+/// of all inputs so far, using Welford's algorithm.
+/// The variance is the population variance.
+#[cfg(feature = "std")]
+pub fn running_stats() -> Func<f64, (f64, f64)> {
+    let state = Mutex::new((0usize, 0.0, 0.0));
+    Arc::new(move |x| {
+        let mut state = state.lock().unwrap();
+        let (n, mean, m2) = &mut *state;
+        *n += 1;
+        let delta = x - *mean;
+        *mean += delta / *n as f64;
+        *m2 += delta * (x - *mean);
+        (*mean, *m2 / *n as f64)
+    })
+}
+
+/// Caches every output of a function.
+///
+/// Repeated inputs are served from the cache without calling the function.
+/// The cache is never cleared, see `memoize_lru` for a bounded cache.
+#[cfg(feature = "std")]
+pub fn memoize<T, U>(f: Func<T, U>) -> Func<T, U>
+    where T: Eq + Hash + Clone + Send + Sync + 'static, U: Clone + Send + Sync + 'static
+{
+    let cache = Mutex::new(HashMap::<T, U>::new());
+    Arc::new(move |t| {
+        if let Some(u) = cache.lock().unwrap().get(&t) {return u.clone()};
+        let u = f(t.clone());
+        cache.lock().unwrap().insert(t, u.clone());
+        u
+    })
+}
+
+/// Caches the most recently used outputs of a function.
+///
+/// At most `capacity` outputs are kept.
+/// When the cache is full, the entry that was least recently used is evicted.
+/// Entries are ordered by last use, so a lookup or eviction costs `O(log capacity)`.
+#[cfg(feature = "std")]
+pub fn memoize_lru<T, U>(f: Func<T, U>, capacity: usize) -> Func<T, U>
+    where T: Eq + Hash + Clone + Send + Sync + 'static, U: Clone + Send + Sync + 'static
+{
+    let cache = Mutex::new(Lru {tick: 0, map: HashMap::new(), order: BTreeMap::new()});
+    Arc::new(move |t| {
+        if let Some(u) = cache.lock().unwrap().get(&t) {return u};
+        let u = f(t.clone());
+        if capacity == 0 {return u};
+        let mut cache = cache.lock().unwrap();
+        if cache.map.len() >= capacity && !cache.map.contains_key(&t) {
+            if let Some((_, k)) = cache.order.pop_first() {cache.map.remove(&k);}
+        }
+        cache.insert(t, u.clone());
+        u
+    })
+}
+
+/// Least recently used cache, ordered by the tick of last use.
+#[cfg(feature = "std")]
+struct Lru<T, U> {
+    tick: u64,
+    map: HashMap<T, (U, u64)>,
+    order: BTreeMap<u64, T>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Eq + Hash + Clone, U: Clone> Lru<T, U> {
+    fn get(&mut self, t: &T) -> Option<U> {
+        self.tick += 1;
+        let (u, used) = self.map.get_mut(t)?;
+        self.order.remove(used);
+        *used = self.tick;
+        self.order.insert(self.tick, t.clone());
+        Some(u.clone())
+    }
+
+    fn insert(&mut self, t: T, u: U) {
+        self.tick += 1;
+        if let Some((_, used)) = self.map.insert(t.clone(), (u, self.tick)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, t);
+    }
+}
+
+/// Emits the output of a function only when it differs from the previous call.
+///
+/// Returns `Some` with the output when it changed, or on the first call,
+/// and `None` otherwise.
+/// Since the previous output is shared by all callers, the result depends on the call order.
+#[cfg(feature = "std")]
+pub fn on_change<T, U>(f: Func<T, U>) -> Func<T, Option<U>>
+    where T: 'static, U: PartialEq + Clone + Send + 'static
+{
+    let last: Mutex<Option<U>> = Mutex::new(None);
+    Arc::new(move |t| {
+        let u = f(t);
+        let mut last = last.lock().unwrap();
+        if last.as_ref() == Some(&u) {return None};
+        *last = Some(u.clone());
+        Some(u)
+    })
+}
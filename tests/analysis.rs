@@ -0,0 +1,79 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn total_variation_monotonic_and_oscillating() {
+    let f: Func<f64, f64> = Arc::new(|t| t * t);
+    assert!((total_variation(&f, 0.0, 2.0, 100) - 4.0).abs() < 1e-12);
+
+    let g: Func<f64, f64> = Arc::new(|t| (t * std::f64::consts::PI * 4.0).sin());
+    let tv = total_variation(&g, 0.0, 1.0, 1000);
+    assert!((g(1.0) - g(0.0)).abs() < 1e-12);
+    assert!((tv - 8.0).abs() < 1e-3);
+}
+
+#[test]
+fn monte_carlo_estimates() {
+    let c: Func<f64, f64> = Arc::new(|_| 3.0);
+    assert!((monte_carlo(&c, 1.0, 3.0, 100, 7) - 6.0).abs() < 1e-12);
+
+    let f: Func<f64, f64> = Arc::new(|t| t * t);
+    let est = monte_carlo(&f, 0.0, 1.0, 100_000, 42);
+    assert!((est - 1.0 / 3.0).abs() < 0.01);
+    assert_eq!(est, monte_carlo(&f, 0.0, 1.0, 100_000, 42));
+    assert_ne!(est, monte_carlo(&f, 0.0, 1.0, 100_000, 43));
+}
+
+#[test]
+fn is_monotonic_directions() {
+    let inc: Func<f64, f64> = Arc::new(|t| t * t * t);
+    let dec: Func<f64, f64> = Arc::new(|t| -t);
+    let osc: Func<f64, f64> = Arc::new(|t| t.sin());
+    let flat: Func<f64, f64> = Arc::new(|_| 1.0);
+    assert_eq!(is_monotonic(&inc, -1.0, 1.0, 100), Some(true));
+    assert_eq!(is_monotonic(&dec, -1.0, 1.0, 100), Some(false));
+    assert_eq!(is_monotonic(&osc, 0.0, 10.0, 100), None);
+    assert_eq!(is_monotonic(&flat, 0.0, 1.0, 10), Some(true));
+}
+
+#[test]
+fn integrate_struct_constant_and_linear() {
+    let c: Func<f64, f64> = Arc::new(|_| 2.5);
+    assert!((integrate_struct::<f64>(&c, 0.0, 1.0, 10) - 2.5).abs() < 1e-12);
+    assert!((integrate_struct::<f64>(&c, 1.0, 3.0, 7) - 2.5).abs() < 1e-12);
+
+    let l: Func<f64, f64> = Arc::new(|t| t);
+    assert!((integrate_struct::<f64>(&l, 0.0, 2.0, 4) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn fit_polynomial_recovers_quadratic() {
+    let f: Func<f64, f64> = Arc::new(|t| 2.0 - 3.0 * t + 0.5 * t * t);
+    let c = fit_polynomial(&f, 2, -2.0, 3.0, 50);
+    assert_eq!(c.len(), 3);
+    for (a, b) in c.iter().zip([2.0, -3.0, 0.5].iter()) {
+        assert!((a - b).abs() < 1e-9);
+    }
+    let p = polynomial(c);
+    assert!((p(1.5) - f(1.5)).abs() < 1e-9);
+}
+
+#[test]
+fn snapshot_samples_endpoints() {
+    let f: Func<f64, f64> = Arc::new(|t| t * 10.0);
+    let q: Vec<f64> = snapshot(&f, 0.0, 1.0, 5);
+    assert_eq!(q, vec![0.0, 2.5, 5.0, 7.5, 10.0]);
+    assert_eq!(snapshot::<f64>(&f, 0.5, 1.0, 1), vec![5.0]);
+    assert!(snapshot::<f64>(&f, 0.0, 1.0, 0).is_empty());
+}
+
+#[test]
+fn discontinuities_finds_junction() {
+    let f: Func<f64, f64> = Arc::new(|t| if t < 0.5 {1.0} else {3.0});
+    let seams = discontinuities(&f, 0.0, 1.0, 100, 0.5);
+    assert_eq!(seams.len(), 1);
+    assert!((seams[0] - 0.5).abs() <= 0.01);
+
+    let g: Func<f64, f64> = Arc::new(|t| t);
+    assert!(discontinuities(&g, 0.0, 1.0, 100, 0.5).is_empty());
+}
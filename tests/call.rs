@@ -0,0 +1,60 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn into_closure_calls_value() {
+    let f: Func<f64, f64> = Arc::new(|t| t * 3.0);
+    let c = into_closure::<f64, f64>(f);
+    assert_eq!(c(2.0), 6.0);
+    let q: Vec<f64> = vec![1.0, 2.0].into_iter().map(c).collect();
+    assert_eq!(q, vec![3.0, 6.0]);
+}
+
+#[test]
+fn post_call_transforms_result() {
+    let f: Func<f64, f64> = Arc::new(|t| t + 1.0);
+    let c = post_call::<f64, f64, _>(f, |x| x * 2.0);
+    assert_eq!(c(2.0), 6.0);
+}
+
+#[test]
+fn saturating_output() {
+    use std::num::Saturating;
+
+    let f: Func<u32, Saturating<u8>> = Arc::new(|x| Saturating(250) + Saturating(x as u8));
+    assert_eq!(<Saturating<u8> as Call<u32>>::call(&f, 3), Saturating(253));
+    assert_eq!(<Saturating<u8> as Call<u32>>::call(&f, 10), Saturating(255));
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Material {
+    name: String,
+    roughness: f64,
+}
+
+impl HoLeaf for Material {}
+
+#[test]
+fn custom_leaf_value() {
+    let m = Material {name: "stone".into(), roughness: 0.8};
+    let f: Fun<Arg<f64>, Material> = {
+        let m = m.clone();
+        Arc::new(move |_| m.clone())
+    };
+    assert_eq!(<Material as Call<f64>>::call(&f, 0.3), m);
+    let q: [Material; 2] = [0.0, 1.0].hmap(&f);
+    assert_eq!(q[1].roughness, 0.8);
+}
+
+#[test]
+fn call_each_shares_argument() {
+    let fs: Vec<Func<f64, f64>> = vec![Arc::new(|t| t), Arc::new(|t| t * t), Arc::new(|t| -t)];
+    let q: Vec<f64> = call_each(&fs, 3.0);
+    assert_eq!(q, vec![3.0, 9.0, -3.0]);
+}
+
+#[test]
+fn func_wraps_closure() {
+    let f: Func<f64, f64> = func(|t| t * 4.0);
+    assert_eq!(<f64 as Call<f64>>::call(&f, 0.5), 2.0);
+}
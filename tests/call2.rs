@@ -0,0 +1,26 @@
+mod common;
+
+use common::{Point, PointFunc};
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn call2_scalar() {
+    let f: Func<(f64, f64), f64> = Arc::new(|(a, b)| a * b);
+    assert_eq!(<f64 as Call2<f64, f64>>::call2(&f, 3.0, 4.0), 12.0);
+}
+
+#[test]
+fn call2_surface_matches_tupled() {
+    let surface: PointFunc<(f64, f64)> = Point {
+        x: Arc::new(|(u, v)| u + v),
+        y: Arc::new(|(u, v)| u - v),
+        z: Arc::new(|(u, v)| u * v),
+    };
+    for &(u, v) in &[(0.0, 0.0), (1.0, 2.0), (-0.5, 3.0), (2.5, -1.5)] {
+        let a = <Point as Call2<f64, f64>>::call2(&surface, u, v);
+        let b = <Point as Call<(f64, f64)>>::call(&surface, (u, v));
+        assert_eq!(a.coords(), b.coords());
+        assert_eq!(a.coords(), [u + v, u - v, u * v]);
+    }
+}
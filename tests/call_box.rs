@@ -0,0 +1,45 @@
+mod common;
+
+use common::circle;
+use ha::*;
+
+struct BoxPoint<T = ()> where f64: BoxHo<T> {
+    x: <f64 as BoxHo<T>>::Fun,
+    y: <f64 as BoxHo<T>>::Fun,
+}
+
+type BoxPointFunc<T> = BoxPoint<Arg<T>>;
+
+impl<T> BoxHo<Arg<T>> for BoxPoint {
+    type Fun = BoxPointFunc<T>;
+}
+
+impl<T: Copy> BoxCall<T> for BoxPoint {
+    fn call_box(f: &Self::Fun, val: T) -> BoxPoint {
+        BoxPoint::<()> {
+            x: <f64 as BoxCall<T>>::call_box(&f.x, val),
+            y: <f64 as BoxCall<T>>::call_box(&f.y, val),
+        }
+    }
+}
+
+#[test]
+fn scalar_box_call() {
+    let f: FuncBox<f64, f64> = Box::new(|t| t * t + 1.0);
+    assert_eq!(<f64 as BoxCall<f64>>::call_box(&f, 3.0), 10.0);
+}
+
+#[test]
+fn box_matches_arc() {
+    let f: BoxPointFunc<f64> = BoxPoint {
+        x: Box::new(|t| t.cos()),
+        y: Box::new(|t| t.sin()),
+    };
+    let g = circle();
+    for i in 0..8 {
+        let t = i as f64 * 0.7;
+        let a = BoxPoint::call_box(&f, t);
+        let b = <common::Point as Call<f64>>::call(&g, t);
+        assert_eq!([a.x, a.y], [b.x, b.y]);
+    }
+}
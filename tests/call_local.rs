@@ -0,0 +1,39 @@
+use ha::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(Clone)]
+struct Point<T = ()> where f64: HoLocal<T> {
+    x: <f64 as HoLocal<T>>::Fun,
+    y: <f64 as HoLocal<T>>::Fun,
+}
+
+type PointFuncLocal<T> = Point<Arg<T>>;
+
+impl<T: Clone> HoLocal<Arg<T>> for Point {
+    type Fun = PointFuncLocal<T>;
+}
+
+impl<T: Copy> CallLocal<T> for Point {
+    fn call_local(f: &Self::Fun, val: T) -> Point {
+        Point::<()> {
+            x: <f64 as CallLocal<T>>::call_local(&f.x, val),
+            y: <f64 as CallLocal<T>>::call_local(&f.y, val),
+        }
+    }
+}
+
+#[test]
+fn rc_capturing_point() {
+    let scale = Rc::new(Cell::new(2.0));
+    let s = scale.clone();
+    let f: PointFuncLocal<f64> = Point {
+        x: Rc::new(move |t| t * s.get()),
+        y: Rc::new(|t| t + 1.0),
+    };
+    let p = Point::call_local(&f, 3.0);
+    assert_eq!((p.x, p.y), (6.0, 4.0));
+    scale.set(10.0);
+    let p = Point::call_local(&f.clone(), 3.0);
+    assert_eq!((p.x, p.y), (30.0, 4.0));
+}
@@ -0,0 +1,49 @@
+use ha::*;
+
+#[test]
+fn counter_increments() {
+    let mut count = 0;
+    let mut f: FuncMut<(), u32> = Box::new(move |()| {
+        count += 1;
+        count
+    });
+    assert_eq!(<u32 as CallMut<()>>::call_mut(&mut f, ()), 1);
+    assert_eq!(<u32 as CallMut<()>>::call_mut(&mut f, ()), 2);
+    assert_eq!(<u32 as CallMut<()>>::call_mut(&mut f, ()), 3);
+}
+
+struct Pair<T = ()> where f64: HoMut<T> {
+    a: <f64 as HoMut<T>>::Fun,
+    b: <f64 as HoMut<T>>::Fun,
+}
+
+type PairFuncMut<T> = Pair<Arg<T>>;
+
+impl<T> HoMut<Arg<T>> for Pair {
+    type Fun = PairFuncMut<T>;
+}
+
+impl<T: Copy> CallMut<T> for Pair {
+    fn call_mut(f: &mut Self::Fun, val: T) -> Pair {
+        Pair::<()> {
+            a: <f64 as CallMut<T>>::call_mut(&mut f.a, val),
+            b: <f64 as CallMut<T>>::call_mut(&mut f.b, val),
+        }
+    }
+}
+
+#[test]
+fn struct_state_is_observable() {
+    let mut offset = 0.0;
+    let mut f: PairFuncMut<f64> = Pair {
+        a: Box::new(move |t| {
+            offset += 1.0;
+            t + offset
+        }),
+        b: Box::new(|t| t * 2.0),
+    };
+    let p = Pair::call_mut(&mut f, 1.0);
+    assert_eq!((p.a, p.b), (2.0, 2.0));
+    let p = Pair::call_mut(&mut f, 1.0);
+    assert_eq!((p.a, p.b), (3.0, 2.0));
+}
@@ -0,0 +1,253 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn product_of_different_types() {
+    let f: Func<f64, u32> = Arc::new(|a| (a * 2.0) as u32);
+    let g: Func<&'static str, usize> = Arc::new(|b| b.len());
+    let h = product(f, g);
+    assert_eq!(h((3.0, "hello")), (6, 5));
+}
+
+#[test]
+fn time_scale_speeds_up() {
+    let f: Func<f64, f64> = Arc::new(|t| t * t + 1.0);
+    let g = time_scale(f.clone(), 2.0);
+    assert_eq!(g(0.5), f(1.0));
+    assert_eq!(time_scale(f.clone(), 0.5)(4.0), f(2.0));
+}
+
+#[test]
+fn compose_all_left_to_right() {
+    let f: Func<f64, f64> = Arc::new(|t| t + 1.0);
+    let g: Func<f64, f64> = Arc::new(|t| t * 2.0);
+    let h: Func<f64, f64> = Arc::new(|t| t - 3.0);
+    let c = compose_all(vec![f.clone(), g.clone(), h.clone()]);
+    assert_eq!(c(5.0), h(g(f(5.0))));
+    assert_eq!(compose_all(vec![])(5.0), 5.0);
+}
+
+#[test]
+fn with_gradient_of_square() {
+    let f: Func<f64, f64> = Arc::new(|t| t * t);
+    let (v, g) = with_gradient(f, 1e-4)(3.0);
+    assert_eq!(v, 9.0);
+    assert!((g - 6.0).abs() < 1e-6);
+    assert!((derivative(|t| t * t * t, 2.0, 1e-4) - 12.0).abs() < 1e-6);
+}
+
+#[test]
+fn map_pair_components() {
+    let f: Func<f64, (f64, u32)> = Arc::new(|t| (t, 3));
+    let g = map_second(f.clone(), |b| b as f64 * 2.0);
+    assert_eq!(g(1.5), (1.5, 6.0));
+    let h = map_first(f, |a| a > 1.0);
+    assert_eq!(h(1.5), (true, 3));
+}
+
+#[test]
+fn switch_selects_option() {
+    let selector: Func<f64, usize> = Arc::new(|t| t as usize);
+    let a: Func<f64, f64> = Arc::new(|t| t);
+    let b: Func<f64, f64> = Arc::new(|t| t * 10.0);
+    let c: Func<f64, f64> = Arc::new(|t| -t);
+    let f = switch(selector, vec![a, b, c]);
+    assert_eq!(f(0.5), 0.5);
+    assert_eq!(f(1.5), 15.0);
+    assert_eq!(f(2.5), -2.5);
+    assert_eq!(f(9.0), -9.0);
+}
+
+#[test]
+#[should_panic(expected = "at least one option")]
+fn switch_without_options() {
+    let selector: Func<f64, usize> = Arc::new(|_| 0);
+    let _ = switch::<f64, f64>(selector, vec![]);
+}
+
+#[test]
+fn moving_average_window() {
+    let data = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+    let f: Func<usize, f64> = Arc::new(move |i| data[i % data.len()]);
+    let avg = moving_average(f, 2);
+    assert_eq!(avg(4), (4.0 + 1.0 + 5.0 + 9.0 + 2.0) / 5.0);
+    assert_eq!(avg(0), (3.0 + 1.0 + 4.0) / 3.0);
+    assert_eq!(avg(1), (3.0 + 1.0 + 4.0 + 1.0) / 4.0);
+    assert_eq!(moving_average(Arc::new(|i| i as f64), 0)(7), 7.0);
+    let g = moving_average(Arc::new(|i| (i % 4) as f64), 2);
+    assert_eq!(g(usize::MAX), (1.0 + 2.0 + 3.0) / 3.0);
+}
+
+#[test]
+fn vectorize_doubles() {
+    let f: Func<f64, f64> = Arc::new(|t| t * 2.0);
+    let g = vectorize(f);
+    assert_eq!(g(vec![1.0, 2.5, -3.0]), vec![2.0, 5.0, -6.0]);
+    assert_eq!(g(vec![]), vec![]);
+}
+
+#[test]
+fn invert_square() {
+    let f: Func<f64, f64> = Arc::new(|x| x * x);
+    let g = invert(f, (0.0, 2.0), 1e-12);
+    assert!((g(4.0) - 2.0).abs() < 1e-9);
+    assert!((g(2.0) - 2f64.sqrt()).abs() < 1e-9);
+    assert!((g(9.0) - 2.0).abs() < 1e-9);
+    assert!(g(-1.0).abs() < 1e-9);
+
+    let h = invert(Arc::new(|x| 1.0 - x), (0.0, 1.0), 1e-12);
+    assert!((h(0.25) - 0.75).abs() < 1e-9);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn morph_crossfades() {
+    let a: Func<f64, f64> = Arc::new(|_| 2.0);
+    let b: Func<f64, f64> = Arc::new(|_| 4.0);
+    let c: Func<f64, f64> = Arc::new(|_| 0.0);
+    let m = morph(vec![a.clone(), b.clone()]);
+    assert_eq!(m(0.0), 2.0);
+    assert_eq!(m(0.5), 3.0);
+    assert_eq!(m(1.0), 4.0);
+
+    let m = morph(vec![a.clone(), b, c]);
+    assert_eq!(m(0.5), 4.0);
+    assert_eq!(m(0.75), 2.0);
+    assert_eq!(morph(vec![a])(0.3), 2.0);
+    assert_eq!(morph(vec![])(0.3), 0.0);
+}
+
+#[test]
+fn jitter_is_deterministic_and_bounded() {
+    let f: Func<f64, f64> = Arc::new(|t| t);
+    let g = jitter(f.clone(), 0.1, 7);
+    let h = jitter(f, 0.1, 7);
+    let mut differs = false;
+    for i in 0..1000 {
+        let t = i as f64 * 0.01;
+        assert_eq!(g(t), h(t));
+        assert!((g(t) - t).abs() <= 0.1);
+        if (g(t) - t).abs() > 1e-6 {differs = true}
+    }
+    assert!(differs);
+}
+
+#[test]
+fn gradient_2d_of_paraboloid() {
+    let f: Func<[f64; 2], f64> = Arc::new(|[x, y]: [f64; 2]| x * x + y * y);
+    let g = gradient_2d(f, 1e-5);
+    let [gx, gy] = g([1.0, 1.0]);
+    assert!((gx - 2.0).abs() < 1e-6 && (gy - 2.0).abs() < 1e-6);
+    let [gx, gy] = g([-0.5, 3.0]);
+    assert!((gx + 1.0).abs() < 1e-6 && (gy - 6.0).abs() < 1e-6);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn posterize_snaps_to_levels() {
+    let f: Func<f64, f64> = Arc::new(|t| t);
+    let p = posterize(f.clone(), 5, (0.0, 1.0));
+    let levels = [0.0, 0.25, 0.5, 0.75, 1.0];
+    for i in 0..=100 {
+        let y = p(i as f64 * 0.013 - 0.2);
+        assert!(levels.contains(&y));
+    }
+    assert_eq!(p(0.3), 0.25);
+    assert_eq!(p(0.4), 0.5);
+    assert_eq!(p(2.0), 1.0);
+    assert_eq!(posterize(f.clone(), 1, (2.0, 4.0))(0.0), 3.0);
+    assert_eq!(posterize(f, 4, (2.0, 2.0))(0.5), 2.0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn by_arc_length_equidistant() {
+    let curve: Func<f64, [f64; 2]> = Arc::new(|t| [t * t * t, t * t]);
+    let f = by_arc_length(curve.clone(), 1000);
+    let points: Vec<[f64; 2]> = (0..=10).map(|i| f(i as f64 / 10.0)).collect();
+    let steps: Vec<f64> = points.windows(2)
+        .map(|w| [w[1][0] - w[0][0], w[1][1] - w[0][1]].length())
+        .collect();
+    let mean = steps.iter().sum::<f64>() / steps.len() as f64;
+    assert!(steps.iter().all(|s| (s - mean).abs() < 1e-3 * mean.max(1.0)));
+    assert_eq!(f(0.0), curve(0.0));
+    assert!((f(1.0)[0] - 1.0).abs() < 1e-12);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn angular_wraps_around() {
+    use std::f64::consts::PI;
+
+    let f: Func<f64, f64> = Arc::new(|t| t);
+    let g = angular(f);
+    assert!((g(2.0 * PI + 0.1) - g(0.1)).abs() < 1e-12);
+    assert!((g(-0.1) - (2.0 * PI - 0.1)).abs() < 1e-12);
+    assert!((g(-4.0 * PI + 1.0) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn compose_is_associative() {
+    let f: Func<f64, f64> = Arc::new(|t| t + 1.0);
+    let g: Func<f64, f64> = Arc::new(|t| t * 3.0);
+    let h: Func<f64, i64> = Arc::new(|t| t as i64 - 2);
+    let left = compose(compose(f.clone(), g.clone()), h.clone());
+    let right = compose(f, compose(g, h));
+    for &t in &[-2.0, 0.0, 0.5, 4.0] {
+        assert_eq!(left(t), right(t));
+    }
+    assert_eq!(left(4.0), 13);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn antiderivative_of_constant_and_linear() {
+    let c: Func<f64, f64> = Arc::new(|_| 2.5);
+    let f = antiderivative(c, 10);
+    assert!((f(4.0) - 10.0).abs() < 1e-12);
+    assert!((f(-2.0) + 5.0).abs() < 1e-12);
+    assert_eq!(f(0.0), 0.0);
+    assert!(f(f64::NAN).is_nan() && f(f64::INFINITY).is_nan() && f(f64::NEG_INFINITY).is_nan());
+
+    let g = antiderivative(Arc::new(|t| t), 100);
+    assert!((g(3.0) - 4.5).abs() < 1e-9);
+}
+
+#[test]
+fn nan_safe_substitutes_fallback() {
+    let f: Func<f64, f64> = Arc::new(|t| (t - 1.0) / (t - 1.0) * t.ln());
+    let g = nan_safe(f.clone(), -1.0);
+    assert!(f(1.0).is_nan());
+    assert_eq!(g(1.0), -1.0);
+    assert_eq!(g(0.0), -1.0);
+    assert_eq!(g(2.0), f(2.0));
+    assert_eq!(g(std::f64::consts::E), 1.0);
+}
+
+#[test]
+fn timeline_crossfades_clips() {
+    let a: Func<f64, f64> = Arc::new(|_| 1.0);
+    let b: Func<f64, f64> = Arc::new(|_| 3.0);
+    let f = timeline(vec![(2.0, a), (2.0, b)], 0.5);
+    assert_eq!(f(-1.0), 1.0);
+    assert_eq!(f(1.0), 1.0);
+    assert_eq!(f(1.5), 1.0);
+    assert_eq!(f(1.75), 2.0);
+    assert_eq!(f(2.0), 3.0);
+    assert_eq!(f(3.5), 3.0);
+    assert_eq!(f(10.0), 3.0);
+
+    let clock: Func<f64, f64> = Arc::new(|t| t);
+    let g = timeline(vec![(1.0, clock.clone()), (1.0, clock)], 0.0);
+    assert_eq!(g(0.5), 0.5);
+    assert_eq!(g(1.25), 0.25);
+
+    assert_eq!(timeline(vec![], 1.0)(0.5), 0.0);
+}
+
+#[test]
+#[should_panic]
+fn timeline_rejects_crossfade_longer_than_clip() {
+    let a: Func<f64, f64> = Arc::new(|_| 1.0);
+    timeline(vec![(2.0, a.clone()), (0.5, a.clone()), (2.0, a)], 1.0);
+}
@@ -0,0 +1,47 @@
+//! Higher order point used by the tests, as declared in the crate docs.
+
+#![allow(dead_code)]
+
+use ha::*;
+use std::sync::Arc;
+
+/// Higher order 3D point.
+#[derive(Clone)]
+pub struct Point<T = ()> where f64: Ho<T> {
+    pub x: Fun<T, f64>,
+    pub y: Fun<T, f64>,
+    pub z: Fun<T, f64>,
+}
+
+impl Point {
+    pub fn coords(&self) -> [f64; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+pub type PointFunc<T> = Point<Arg<T>>;
+
+impl<T: Clone> Ho<Arg<T>> for Point {
+    type Fun = PointFunc<T>;
+}
+
+impl<T: Copy> Call<T> for Point
+    where f64: Ho<Arg<T>> + Call<T>
+{
+    fn call(f: &Self::Fun, val: T) -> Point {
+        Point::<()> {
+            x: <f64 as Call<T>>::call(&f.x, val),
+            y: <f64 as Call<T>>::call(&f.y, val),
+            z: <f64 as Call<T>>::call(&f.z, val),
+        }
+    }
+}
+
+pub fn circle() -> PointFunc<f64> {
+    Point {
+        x: Arc::new(|t| t.cos()),
+        y: Arc::new(|t| t.sin()),
+        z: Arc::new(|_| 0.0),
+    }
+}
+
@@ -0,0 +1,175 @@
+use ha::*;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+#[cfg(feature = "std")]
+#[test]
+fn from_map_present_and_absent() {
+    let mut table = HashMap::new();
+    table.insert(1, 0.5);
+    table.insert(2, 0.25);
+    let f = from_map(table, -1.0);
+    assert_eq!(f(1), 0.5);
+    assert_eq!(f(2), 0.25);
+    assert_eq!(f(3), -1.0);
+}
+
+#[test]
+fn gradient_stops_and_midpoint() {
+    let f = gradient(vec![(1.0, [0.0, 0.0, 1.0]), (0.0, [1.0, 0.0, 0.0])]);
+    assert_eq!(f(0.0), [1.0, 0.0, 0.0]);
+    assert_eq!(f(1.0), [0.0, 0.0, 1.0]);
+    assert_eq!(f(0.5), [0.5, 0.0, 0.5]);
+    assert_eq!(f(-1.0), [1.0, 0.0, 0.0]);
+    assert_eq!(f(2.0), [0.0, 0.0, 1.0]);
+
+    let c = gradient(vec![(0.3, [0.2, 0.4, 0.6])]);
+    assert_eq!(c(0.0), [0.2, 0.4, 0.6]);
+    assert_eq!(c(1.0), [0.2, 0.4, 0.6]);
+}
+
+#[test]
+fn gradient_nan_argument() {
+    let f = gradient(vec![(0.0, [1.0, 0.0, 0.0]), (1.0, [0.0, 0.0, 1.0])]);
+    assert!(f(f64::NAN).iter().all(|c| c.is_nan()));
+}
+
+#[test]
+fn nearest_on_and_between_samples() {
+    let f = nearest(vec![(1.0, 10.0), (0.0, 5.0), (2.0, 20.0)]);
+    assert_eq!(f(0.0), 5.0);
+    assert_eq!(f(1.0), 10.0);
+    assert_eq!(f(2.0), 20.0);
+    assert_eq!(f(0.4), 5.0);
+    assert_eq!(f(0.6), 10.0);
+    assert_eq!(f(0.5), 5.0);
+    assert_eq!(f(-3.0), 5.0);
+    assert_eq!(f(7.0), 20.0);
+    assert!(nearest(vec![])(1.0).is_nan());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn damped_sine_envelope_and_period() {
+    let f = damped_sine(2.0, 0.5);
+    assert_eq!(f(0.0), 0.0);
+    for i in 0..100 {
+        let t = i as f64 * 0.037;
+        assert!(f(t).abs() <= (-0.5 * t).exp() + 1e-12);
+    }
+    // One period later the value is scaled by the envelope.
+    let t = 0.1;
+    assert!((f(t + 0.5) - f(t) * (-0.25f64).exp()).abs() < 1e-12);
+    assert!(f(10.125).abs() < f(0.125).abs());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sigmoid_midpoint_and_asymptotes() {
+    let f = sigmoid(4.0, 2.0);
+    assert_eq!(f(2.0), 0.5);
+    assert!(f(10.0) > 1.0 - 1e-9 && f(10.0) < 1.0);
+    assert!(f(-6.0) < 1e-9 && f(-6.0) > 0.0);
+    assert!(f(2.5) > f(2.0));
+}
+
+#[test]
+fn table_lookup_large_table() {
+    let table: Vec<(f64, f64)> = (0..1000).map(|i| (i as f64, (i * i) as f64)).collect();
+    let f = table_lookup(table);
+    assert_eq!(f(10.0), 100.0);
+    assert_eq!(f(10.5), 110.5);
+    assert_eq!(f(998.25), 998.0 * 998.0 + 0.25 * 1997.0);
+    assert_eq!(f(-5.0), 0.0);
+    assert_eq!(f(5000.0), 999.0 * 999.0);
+    let sum: f64 = (0..100_000).map(|i| f(i as f64 * 0.00999)).sum();
+    assert!(sum > 0.0);
+    assert!(table_lookup(vec![])(1.0).is_nan());
+    assert!(f(f64::NAN).is_nan());
+}
+
+#[test]
+fn polynomial_horner() {
+    let p = polynomial(vec![1.0, 2.0, 3.0]);
+    assert_eq!(p(0.0), 1.0);
+    assert_eq!(p(2.0), 17.0);
+    assert_eq!(polynomial(vec![])(3.0), 0.0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recurrence_fibonacci() {
+    let fib = recurrence(vec![0.0, 1.0], |w| w[0] + w[1]);
+    assert_eq!(fib(10), 55.0);
+    assert_eq!(fib(0), 0.0);
+    assert_eq!(fib(1), 1.0);
+    assert_eq!(fib(5), 5.0);
+    assert_eq!(fib(20), 6765.0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recurrence_recovers_from_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let fail = std::sync::Arc::new(AtomicBool::new(true));
+    let f = fail.clone();
+    let seq = recurrence(vec![1.0], move |w| {
+        if w[0] >= 4.0 && f.load(Ordering::SeqCst) {panic!("step failed")};
+        w[0] * 2.0
+    });
+    assert!(catch_unwind(AssertUnwindSafe(|| seq(5))).is_err());
+    assert_eq!(seq(2), 4.0);
+    fail.store(false, Ordering::SeqCst);
+    assert_eq!(seq(5), 32.0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn sphere_sdf_distances() {
+    let f = sphere_sdf([1.0, 2.0, 3.0], 2.0);
+    assert_eq!(f([1.0, 2.0, 3.0]), -2.0);
+    assert_eq!(f([3.0, 2.0, 3.0]), 0.0);
+    assert_eq!(f([1.0, 2.0, 0.0]), 1.0);
+    assert_eq!(f([1.0, 7.0, 3.0]), 3.0);
+}
+
+#[test]
+fn konst_and_identity() {
+    let c: Func<f64, f64> = konst(5.0);
+    assert_eq!(c(0.0), 5.0);
+    assert_eq!(c(-3.5), 5.0);
+    let s: Func<i32, String> = konst("flat".to_string());
+    assert_eq!(s(7), "flat");
+
+    let id = identity::<(i32, &'static str)>();
+    assert_eq!(id((3, "a")), (3, "a"));
+    assert_eq!(identity::<f64>()(2.5), 2.5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn gaussian_peak_and_symmetry() {
+    let f = gaussian(1.5, 0.5);
+    assert_eq!(f(1.5), 1.0);
+    for &d in &[0.1, 0.5, 2.0] {
+        assert!(f(1.5 + d) < 1.0);
+        assert!((f(1.5 + d) - f(1.5 - d)).abs() < 1e-15);
+    }
+    assert!((f(2.0) - (-0.5f64).exp()).abs() < 1e-15);
+
+    let g = gaussian_normalized(0.0, 2.0);
+    let peak = 1.0 / (2.0 * (2.0 * std::f64::consts::PI).sqrt());
+    assert!((g(0.0) - peak).abs() < 1e-15);
+    assert!((g(1.0) - g(-1.0)).abs() < 1e-15);
+    let area: f64 = (-2000..=2000).map(|i| g(i as f64 * 0.01) * 0.01).sum();
+    assert!((area - 1.0).abs() < 1e-6);
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[should_panic]
+fn gaussian_rejects_zero_std_dev() {
+    gaussian(0.0, 0.0);
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "derive")]
+
+mod common;
+
+use common::circle;
+use ha::*;
+use std::sync::Arc;
+
+#[derive(Clone, Ho, Call)]
+pub struct Point2<T = ()> where f64: Ho<T> {
+    pub x: Fun<T, f64>,
+    pub y: Fun<T, f64>,
+}
+
+#[derive(Clone, Ho, Call)]
+struct Color<T = ()> where f32: Ho<T> {
+    r: Fun<T, f32>,
+    g: Fun<T, f32>,
+    b: Fun<T, f32>,
+}
+
+#[derive(Clone, Ho, Call)]
+struct Segment<T = ()> where Point2: Ho<T> {
+    start: Fun<T, Point2>,
+    end: Fun<T, Point2>,
+}
+
+#[derive(Clone, Ho, Call, HoBuilder)]
+struct Pair<T = ()>(Fun<T, f64>, Fun<T, u8>) where f64: Ho<T>, u8: Ho<T>;
+
+#[derive(Clone, Ho, Call)]
+struct Wide<T = ()> where f64: Ho<T>, (f64, f64): Clone {
+    x: Fun<T, f64>,
+}
+
+#[derive(Clone, Ho, Call)]
+struct WideTuple<T = ()>(Fun<T, f64>) where f64: Ho<T>, (f64, f64): Clone;
+
+#[test]
+fn where_clause_with_groups() {
+    let f: WideFunc<f64> = Wide {x: Arc::new(|t| t + 1.0)};
+    assert_eq!(<Wide as Call<f64>>::call(&f, 1.0).x, 2.0);
+    let triple: Func<f64, f64> = Arc::new(|t| t * 3.0);
+    let f: WideTupleFunc<f64> = WideTuple(triple);
+    assert_eq!(<WideTuple as Call<f64>>::call(&f, 1.0).0, 3.0);
+}
+
+#[test]
+fn two_fields() {
+    let f: Fun<Arg<f64>, Point2> = Point2Func {
+        x: Arc::new(|t| t * 2.0),
+        y: Arc::new(|t| t + 1.0),
+    };
+    let f: Point2Func<f64> = f.clone();
+    assert_eq!(((f.x)(3.0), (f.y)(3.0)), (6.0, 4.0));
+}
+
+#[test]
+fn three_fields() {
+    let f: ColorFunc<f32> = Color {
+        r: Arc::new(|t| t),
+        g: Arc::new(|t| 1.0 - t),
+        b: Arc::new(|_| 0.5),
+    };
+    let c = Color::<()> {r: (f.r)(0.25), g: (f.g)(0.25), b: (f.b)(0.25)};
+    assert_eq!([c.r, c.g, c.b], [0.25, 0.75, 0.5]);
+}
+
+#[test]
+fn nested_and_tuple_structs() {
+    let start: Point2Func<f64> = Point2 {x: Arc::new(|t| t), y: Arc::new(|_| 0.0)};
+    let f: SegmentFunc<f64> = Segment {start: start.clone(), end: start};
+    assert_eq!(((f.start.x)(2.0), (f.end.y)(2.0)), (2.0, 0.0));
+
+    let neg: Func<f64, f64> = Arc::new(|t| -t);
+    let byte: Func<f64, u8> = Arc::new(|t| t as u8);
+    let p: PairFunc<f64> = Pair(neg, byte);
+    assert_eq!(((p.0)(2.0), (p.1)(2.0)), (-2.0, 2));
+}
+
+#[derive(Clone, Ho, Call, HoFields, HoBuilder, HAccumulate, HDiff, HDerivative)]
+struct Point3<T = ()> where f64: Ho<T> {
+    x: Fun<T, f64>,
+    y: Fun<T, f64>,
+    z: Fun<T, f64>,
+}
+
+#[test]
+fn call_matches_hand_written() {
+    let reference = circle();
+    let f: Point3Func<f64> = Point3 {
+        x: reference.x.clone(),
+        y: reference.y.clone(),
+        z: reference.z.clone(),
+    };
+    for i in 0..10 {
+        let t = i as f64 * 0.6;
+        let a = <Point3 as Call<f64>>::call(&f, t);
+        let b = <common::Point as Call<f64>>::call(&reference, t);
+        assert_eq!([a.x, a.y, a.z], b.coords());
+    }
+}
+
+#[test]
+fn fields_by_name() {
+    assert_eq!(Point3Func::<f64>::field_names(), &["x", "y", "z"]);
+    let f: Point3Func<f64> = Point3 {
+        x: Arc::new(|t| t),
+        y: Arc::new(|t| t * 2.0),
+        z: Arc::new(|t| t * 3.0),
+    };
+    let y = f.get_field("y").unwrap();
+    assert_eq!(y(2.0), 4.0);
+    assert!(f.get_field("w").is_none());
+}
+
+#[test]
+fn builder_from_closures() {
+    let f = Point3Func::from_fns(|t: f64| t, |t| t * t, |_| -1.0);
+    let p = <Point3 as Call<f64>>::call(&f, 3.0);
+    assert_eq!([p.x, p.y, p.z], [3.0, 9.0, -1.0]);
+
+    let p = <Pair as Call<f64>>::call(&PairFunc::from_fns(|t: f64| -t, |t| t as u8), 2.0);
+    assert_eq!((p.0, p.1), (-2.0, 2));
+}
+
+#[test]
+fn accumulate_fields() {
+    let f = Point3Func::from_fns(|_| 2.0, |t: f64| t, |t| 4.0 * t * t * t);
+    let p: Point3 = integrate_struct(&f, 0.0, 1.0, 100);
+    assert!((p.x - 2.0).abs() < 1e-12 && (p.y - 0.5).abs() < 1e-12 && (p.z - 1.0).abs() < 1e-3);
+
+    let a = Tagged::<()> {offset: 1.0, material_id: 3}.hadd(Tagged {offset: 2.0, material_id: 4}).hscale(2.0);
+    assert_eq!((a.offset, a.material_id), (6.0, 3));
+}
+
+#[test]
+fn diff_fields() {
+    let before = Point3Func::from_fns(|t: f64| t, |t| t * t, |_| 1.0);
+    let after = Point3Func::from_fns(|t: f64| 2.0 * t, |t| t, |_| 1.0);
+    let d = after.clone().hdiff(before.clone());
+    for i in 0..5 {
+        let t = i as f64 * 0.5;
+        let (a, b) = (<Point3 as Call<f64>>::call(&after, t), <Point3 as Call<f64>>::call(&before, t));
+        let p = <Point3 as Call<f64>>::call(&d, t);
+        assert_eq!([p.x, p.y, p.z], [a.x - b.x, a.y - b.y, 0.0]);
+        let q = a.hdiff(b);
+        assert_eq!([q.x, q.y, q.z], [p.x, p.y, p.z]);
+    }
+}
+
+#[test]
+fn derivative_of_point_is_velocity() {
+    let c = circle();
+    let f: Point3Func<f64> = Point3 {x: c.x, y: c.y, z: c.z};
+    let v = f.hderivative(1e-5);
+    let p = <Point3 as Call<f64>>::call(&v, 0.0);
+    assert!(p.x.abs() < 1e-9 && (p.y - 1.0).abs() < 1e-9 && p.z == 0.0);
+}
+
+#[test]
+fn call_nested_and_tuple_structs() {
+    let start: Point2Func<f64> = Point2 {x: Arc::new(|t| t), y: Arc::new(|t| t * t)};
+    let end: Point2Func<f64> = Point2 {x: Arc::new(|t| -t), y: Arc::new(|_| 1.0)};
+    let f: SegmentFunc<f64> = Segment {start, end};
+    let s = <Segment as Call<f64>>::call(&f, 3.0);
+    assert_eq!([s.start.x, s.start.y, s.end.x, s.end.y], [3.0, 9.0, -3.0, 1.0]);
+
+    let neg: Func<f64, f64> = Arc::new(|t| -t);
+    let byte: Func<f64, u8> = Arc::new(|t| t as u8 + 1);
+    let p = <Pair as Call<f64>>::call(&Pair(neg, byte), 4.0);
+    assert_eq!((p.0, p.1), (-4.0, 5));
+
+    let g: Point2Func<f64> = Point2 {x: Arc::new(|t| t), y: Arc::new(|t| t * 10.0)};
+    let q: Vec<Point2> = vec![1.0, 2.0].hmap(&g);
+    assert_eq!(q.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>(), vec![(1.0, 10.0), (2.0, 20.0)]);
+}
+
+#[derive(Clone, Debug, PartialEq, HoLeaf)]
+struct Material {
+    roughness: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, HoLeaf)]
+enum Shade {
+    Light,
+    Dark,
+}
+
+#[test]
+fn leaf_fields() {
+    let f: Fun<Arg<f64>, Material> = Arc::new(|t| Material {roughness: t});
+    assert_eq!(<Material as Call<f64>>::call(&f, 0.5), Material {roughness: 0.5});
+    let g: Func<f64, Shade> = Arc::new(|t| if t < 0.5 {Shade::Dark} else {Shade::Light});
+    let q: Vec<Shade> = vec![0.0, 1.0].hmap(&g);
+    assert_eq!(q, vec![Shade::Dark, Shade::Light]);
+}
+
+#[derive(Clone, Ho, Call, HoBuilder, HAccumulate)]
+struct Tagged<T = ()> where f64: Ho<T> {
+    offset: Fun<T, f64>,
+    #[ho(skip)]
+    material_id: u32,
+}
+
+#[test]
+fn skipped_field_is_cloned_through() {
+    let f: TaggedFunc<f64> = Tagged {offset: Arc::new(|t| t * 0.5), material_id: 7};
+    let a = <Tagged as Call<f64>>::call(&f, 4.0);
+    assert_eq!((a.offset, a.material_id), (2.0, 7));
+    let f = TaggedFunc::from_fns(|t: f64| t * 0.5, 7);
+    let q: Vec<Tagged> = vec![1.0, 2.0].hmap(&f);
+    assert_eq!(q.iter().map(|a| (a.offset, a.material_id)).collect::<Vec<_>>(), vec![(0.5, 7), (1.0, 7)]);
+}
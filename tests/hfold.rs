@@ -0,0 +1,23 @@
+use ha::*;
+
+mod common;
+
+use common::{circle, Point};
+
+#[test]
+fn centroid_of_mapped_points() {
+    let p = circle();
+    let angles: Vec<f64> = (0..4).map(|i| i as f64 * std::f64::consts::FRAC_PI_2).collect();
+    let points: Vec<Point> = angles.hmap(&p);
+    let n = points.len() as f64;
+    let [x, y, z] = points.hfold([0.0; 3], |[x, y, z], p| [x + p.x / n, y + p.y / n, z + p.z / n]);
+    assert!(x.abs() < 1e-12 && y.abs() < 1e-12 && z == 0.0);
+}
+
+#[test]
+fn fold_arrays_options_and_empty() {
+    assert_eq!([1, 2, 3].hfold(0, |a, b| a + b), 6);
+    assert_eq!(Some(5).hfold(1, |a, b| a * b), 5);
+    assert_eq!(None::<i32>.hfold(1, |a, b| a * b), 1);
+    assert_eq!(Vec::<f64>::new().hfold(7.5, |a, b| a + b), 7.5);
+}
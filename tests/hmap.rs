@@ -0,0 +1,184 @@
+use ha::*;
+use std::sync::Arc;
+
+mod common;
+
+use common::{circle, Point};
+
+#[test]
+fn array_element_expands_to_vec() {
+    let f: Func<f64, Vec<f64>> = Arc::new(|a| vec![a; a as usize]);
+    let q: [Vec<f64>; 3] = [1.0, 2.0, 3.0].hmap(&f);
+    assert_eq!(q, [vec![1.0], vec![2.0, 2.0], vec![3.0, 3.0, 3.0]]);
+}
+
+#[test]
+fn vec_maps_elementwise() {
+    let f: Func<f64, f64> = Arc::new(|a| a * 2.0);
+    let q: Vec<f64> = vec![1.0, 2.0].hmap(&f);
+    assert_eq!(q, vec![2.0, 4.0]);
+}
+
+#[test]
+fn owned_and_borrowed_array() {
+    let f: Func<f64, f64> = Arc::new(|a| a * a);
+    let data = [1.0, 2.0, 3.0, 4.0];
+    let borrowed: [f64; 4] = (&data).hmap(&f);
+    let owned: [f64; 4] = data.hmap(&f);
+    assert_eq!(owned, borrowed);
+    assert_eq!(owned, [1.0, 4.0, 9.0, 16.0]);
+}
+
+#[test]
+fn result_of_vec() {
+    let f: Func<f64, f64> = Arc::new(|a| a * 10.0);
+    let ok: Result<Vec<f64>, String> = Ok(vec![1.0, 2.0]);
+    let q: Result<Vec<f64>, String> = ok.hmap(&f);
+    assert_eq!(q, Ok(vec![10.0, 20.0]));
+    let err: Result<Vec<f64>, String> = Err("bad input".into());
+    let q: Result<Vec<f64>, String> = err.hmap(&f);
+    assert_eq!(q, Err("bad input".into()));
+}
+
+#[test]
+fn try_hmap_indexed_reports_failing_index() {
+    let f: Func<f64, Result<f64, String>> = Arc::new(|a| {
+        if a < 0.0 {Err(format!("negative: {}", a))} else {Ok(a.sqrt())}
+    });
+    let q: Result<Vec<f64>, (usize, String)> =
+        vec![1.0, 4.0, 9.0, -1.0, -2.0].try_hmap_indexed(&f);
+    assert_eq!(q, Err((3, "negative: -1".into())));
+    let q: Result<Vec<f64>, String> = vec![1.0, 4.0].try_hmap(&f);
+    assert_eq!(q, Ok(vec![1.0, 2.0]));
+
+    let nested: Result<[[f64; 2]; 2], (usize, String)> =
+        [[1.0, 4.0], [9.0, -1.0]].try_hmap_indexed(&f);
+    assert_eq!(nested, Err((3, "negative: -1".into())));
+}
+
+#[test]
+fn hmap_ref_keeps_original() {
+    let f: Func<f64, f64> = Arc::new(|a| a + 1.0);
+    let data = vec![1.0, 2.0, 3.0];
+    let q: Vec<f64> = data.hmap_ref(&f);
+    assert_eq!(q, vec![2.0, 3.0, 4.0]);
+    assert_eq!(data, vec![1.0, 2.0, 3.0]);
+
+    let nested = [[1.0, 2.0], [3.0, 4.0]];
+    let q: [[f64; 2]; 2] = nested.hmap_ref(&f);
+    assert_eq!(q, [[2.0, 3.0], [4.0, 5.0]]);
+    let x: f64 = 5.0.hmap_ref(&f);
+    assert_eq!(x, 6.0);
+}
+
+#[test]
+fn hmap_meta_count_and_depth() {
+    let f: Func<f64, f64> = Arc::new(|a| -a);
+    let (q, count, depth): ([[f64; 2]; 3], _, _) =
+        [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]].hmap_meta(&f);
+    assert_eq!(q, [[-1.0, -2.0], [-3.0, -4.0], [-5.0, -6.0]]);
+    assert_eq!((count, depth), (6, 2));
+
+    let (q, count, depth): (Vec<Vec<f64>>, _, _) = vec![vec![1.0], vec![], vec![2.0, 3.0]].hmap_meta(&f);
+    assert_eq!(q.len(), 3);
+    assert_eq!((count, depth), (3, 2));
+}
+
+#[test]
+fn arrays_of_any_length() {
+    let f: Func<f64, f64> = Arc::new(|a| a + 1.0);
+    let panics: Func<f64, f64> = Arc::new(|_| panic!("must not be called"));
+    let empty: [f64; 0] = [];
+    let q: [f64; 0] = empty.hmap(&panics);
+    assert_eq!(q, []);
+    let q: [f64; 1] = [1.0].hmap(&f);
+    assert_eq!(q, [2.0]);
+    let q: [f64; 7] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0].hmap(&f);
+    assert_eq!(q, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    let data: [f64; 32] = std::array::from_fn(|i| i as f64);
+    let q: [f64; 32] = data.hmap(&f);
+    assert!(q.iter().enumerate().all(|(i, &x)| x == i as f64 + 1.0));
+    let q: [[f64; 2]; 3] = [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]].hmap(&f);
+    assert_eq!(q, [[2.0, 3.0], [4.0, 5.0], [6.0, 7.0]]);
+}
+
+#[test]
+fn array_of_heterogeneous_tuples() {
+    let fx: Func<f64, f64> = Arc::new(|a| a * 10.0);
+    let fi: Func<i32, i32> = Arc::new(|a| -a);
+    let q: [(f64, i32); 3] = [(0.1, 1), (0.2, 2), (0.3, 3)].hmap(&(fx, fi));
+    assert_eq!(q, [(1.0, -1), (2.0, -2), (3.0, -3)]);
+}
+
+#[test]
+fn option_maps_some() {
+    let f: Func<f64, f64> = Arc::new(|a| a * 2.0);
+    let q: Option<f64> = Some(1.5).hmap(&f);
+    assert_eq!(q, Some(3.0));
+    let q: Option<[f64; 2]> = Some([1.0, 2.0]).hmap(&f);
+    assert_eq!(q, Some([2.0, 4.0]));
+}
+
+#[test]
+fn option_none_does_not_call() {
+    let panics: Func<f64, f64> = Arc::new(|_| panic!("must not be called"));
+    let q: Option<f64> = None::<f64>.hmap(&panics);
+    assert_eq!(q, None);
+}
+
+#[derive(Debug, PartialEq)]
+struct ParseError {
+    line: usize,
+    message: String,
+}
+
+#[test]
+fn result_ok_and_err_payload() {
+    let f: Func<f64, f64> = Arc::new(|a| a + 1.0);
+    let q: Result<f64, ParseError> = Ok(1.0).hmap(&f);
+    assert_eq!(q, Ok(2.0));
+
+    let panics: Func<f64, f64> = Arc::new(|_| panic!("must not be called"));
+    let err: Result<f64, ParseError> = Err(ParseError {line: 3, message: "expected number".into()});
+    let q: Result<f64, ParseError> = err.hmap(&panics);
+    assert_eq!(q, Err(ParseError {line: 3, message: "expected number".into()}));
+}
+
+#[test]
+fn btree_map_values() {
+    use std::collections::BTreeMap;
+
+    let f: Func<f64, f64> = Arc::new(|a| a * 2.0);
+    let mut keyframes = BTreeMap::new();
+    keyframes.insert(30u32, 3.0);
+    keyframes.insert(10, 1.0);
+    keyframes.insert(20, 2.0);
+    let q: BTreeMap<u32, f64> = keyframes.clone().hmap(&f);
+    assert!(q.keys().eq(keyframes.keys()));
+    assert_eq!(q.into_iter().collect::<Vec<_>>(), vec![(10, 2.0), (20, 4.0), (30, 6.0)]);
+}
+
+#[test]
+fn vec_deque_front_to_back() {
+    use std::collections::VecDeque;
+
+    let f: Func<f64, f64> = Arc::new(|a| a + 0.5);
+    let mut buf = VecDeque::new();
+    buf.push_back(2.0);
+    buf.push_front(1.0);
+    buf.push_back(3.0);
+    buf.push_front(0.0);
+    let q: VecDeque<f64> = buf.hmap(&f);
+    assert_eq!(q, VecDeque::from(vec![0.5, 1.5, 2.5, 3.5]));
+}
+
+#[test]
+fn boxed_array_of_points() {
+    let p = circle();
+    let q: Box<[Point; 2]> = Box::new([0.0, std::f64::consts::PI]).hmap(&p);
+    assert_eq!(q[0].coords(), [1.0, 0.0, 0.0]);
+    assert_eq!(q[1].x, -1.0);
+
+    let q: Box<[Point]> = vec![0.0, 0.0, 0.0].into_boxed_slice().hmap(&p);
+    assert_eq!(q.len(), 3);
+}
@@ -0,0 +1,69 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn arrays_of_any_length() {
+    let empty: [f64; 0] = [];
+    let e: [(f64, f64); 0] = (empty, empty).hpair();
+    assert_eq!(e, []);
+    assert_eq!(([1.0], [2.0]).hpair(), [(1.0, 2.0)]);
+    let a = [0, 1, 2, 3, 4, 5, 6];
+    let b = [10, 11, 12, 13, 14, 15, 16];
+    let q: [(i32, i32); 7] = (a, b).hpair();
+    assert!(q.iter().enumerate().all(|(i, &p)| p == (a[i], b[i])));
+    assert_eq!(([[1, 2], [3, 4]], [[5, 6], [7, 8]]).hpair(), [[(1, 5), (2, 6)], [(3, 7), (4, 8)]]);
+}
+
+#[test]
+fn pair_then_map() {
+    let mid: Func<(f64, f64), f64> = Arc::new(|(a, b)| (a + b) * 0.5);
+    let a: [f64; 16] = std::array::from_fn(|i| i as f64);
+    let b: [f64; 16] = std::array::from_fn(|i| 2.0 * i as f64);
+    let q: [f64; 16] = (a, b).hpair().hmap(&mid);
+    assert!(q.iter().enumerate().all(|(i, &x)| x == 1.5 * i as f64));
+}
+
+#[test]
+fn pairs_of_pairs() {
+    assert_eq!(((1.0, 2), (3.0, 4)).hpair(), ((1.0, 3.0), (2, 4)));
+    let q = ([(1.0, 2.0), (3.0, 4.0)], [(5.0, 6.0), (7.0, 8.0)]).hpair();
+    assert_eq!(q, [((1.0, 5.0), (2.0, 6.0)), ((3.0, 7.0), (4.0, 8.0))]);
+    let q = (([1, 2], [3, 4]), ([5, 6], [7, 8])).hpair();
+    assert_eq!(q, ([(1, 5), (2, 6)], [(3, 7), (4, 8)]));
+}
+
+#[test]
+fn hzip_matches_two_steps() {
+    let add: Func<(f64, f64), f64> = Arc::new(|(a, b)| a + b);
+    let q: f64 = 1.0.hzip(2.0, &add);
+    assert_eq!(q, 3.0);
+    let mul: Func<(i32, i32), i32> = Arc::new(|(a, b)| a * b);
+    let q: [i32; 2] = [2, 3].hzip([4, 5], &mul);
+    assert_eq!(q, [8, 15]);
+
+    let q: [f64; 3] = [1.0, 2.0, 3.0].hzip([10.0, 20.0, 30.0], &add);
+    let r: [f64; 3] = ([1.0, 2.0, 3.0], [10.0, 20.0, 30.0]).hpair().hmap(&add);
+    assert_eq!(q, r);
+
+    let a = vec![1.0, 2.0, 3.0];
+    let b = vec![10.0, 20.0];
+    let q: Vec<f64> = a.clone().hzip(b.clone(), &add);
+    let r: Vec<f64> = (a, b).hpair().hmap(&add);
+    assert_eq!(q, r);
+    assert_eq!(q, vec![11.0, 22.0]);
+}
+
+#[test]
+fn try_hpair_checks_lengths() {
+    assert_eq!((vec![1, 2], vec![3, 4]).try_hpair(), Ok(vec![(1, 3), (2, 4)]));
+    assert_eq!(
+        (vec![1, 2, 3], vec![4]).try_hpair(),
+        Err(HPairLenError {left: 3, right: 1})
+    );
+    assert_eq!((Vec::<f64>::new(), Vec::<f64>::new()).try_hpair(), Ok(vec![]));
+    assert_eq!(
+        ([vec![1], vec![2, 3]], [vec![4], vec![5]]).try_hpair(),
+        Err(HPairLenError {left: 2, right: 1})
+    );
+    assert_eq!((vec![1, 2, 3], vec![4]).hpair(), vec![(1, 4)]);
+}
@@ -0,0 +1,23 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn triple_arrays_and_vectors() {
+    assert_eq!(([1, 2], [3, 4], [5, 6]).htriple(), [(1, 3, 5), (2, 4, 6)]);
+    let q = (vec![1.0, 2.0, 3.0], vec![4.0, 5.0], vec![6.0, 7.0, 8.0]).htriple();
+    assert_eq!(q, vec![(1.0, 4.0, 6.0), (2.0, 5.0, 7.0)]);
+    assert_eq!(([[1, 2]], [[3, 4]], [[5, 6]]).htriple(), [[(1, 3, 5), (2, 4, 6)]]);
+}
+
+#[test]
+fn triple_then_map_centroid() {
+    let centroid: Func<(f64, f64, f64), f64> = Arc::new(|(a, b, c)| (a + b + c) / 3.0);
+    let xa = [0.0, 3.0, 6.0];
+    let xb = [3.0, 6.0, 9.0];
+    let xc = [0.0, 0.0, 3.0];
+    let q: [f64; 3] = (xa, xb, xc).htriple().hmap(&centroid);
+    assert_eq!(q, [1.0, 3.0, 6.0]);
+
+    let v: Vec<f64> = (xa.to_vec(), xb.to_vec(), xc.to_vec()).htriple().hmap(&centroid);
+    assert_eq!(v, vec![1.0, 3.0, 6.0]);
+}
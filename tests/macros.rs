@@ -0,0 +1,74 @@
+mod common;
+
+use common::{circle, Point, PointFunc};
+use ha::*;
+use std::sync::Arc;
+
+pub trait Dot<Rhs = Self> {
+    type Output;
+    fn dot(self, other: Rhs) -> Self::Output;
+}
+
+higher_order_op! {
+    impl Dot for Point, PointFunc {
+        type Output = f64;
+        fn dot(self, other: Self) -> f64 {
+            self.x * other.x + self.y * other.y + self.z * other.z
+        }
+    }
+}
+
+pub trait Norm2 {
+    type Output;
+    fn norm2(self) -> Self::Output;
+}
+
+higher_order_op! {
+    impl Norm2 for Point, PointFunc {
+        type Output = f64;
+        fn norm2(self) -> f64 {
+            self.x * self.x + self.y * self.y + self.z * self.z
+        }
+    }
+}
+
+// Written by hand as in the crate docs.
+fn manual_dot<T: 'static + Copy>(a: PointFunc<T>, b: PointFunc<T>) -> Func<T, f64> {
+    let (ax, ay, az) = (a.x, a.y, a.z);
+    let (bx, by, bz) = (b.x, b.y, b.z);
+    Arc::new(move |t| ax(t) * bx(t) + ay(t) * by(t) + az(t) * bz(t))
+}
+
+fn line() -> PointFunc<f64> {
+    Point {
+        x: Arc::new(|t| 1.0 + t),
+        y: Arc::new(|t| 2.0 * t),
+        z: Arc::new(|_| 0.5),
+    }
+}
+
+#[test]
+fn ordinary_dot() {
+    let a = Point::<()> {x: 1.0, y: 2.0, z: 3.0};
+    let b = Point::<()> {x: 4.0, y: -5.0, z: 6.0};
+    assert_eq!(a.dot(b), 12.0);
+}
+
+#[test]
+fn higher_order_dot_matches_manual() {
+    let generated = circle().dot(line());
+    let manual = manual_dot(circle(), line());
+    for i in 0..20 {
+        let t = i as f64 * 0.3 - 3.0;
+        assert_eq!(generated(t), manual(t));
+    }
+}
+
+#[test]
+fn higher_order_unary() {
+    let f = circle().norm2();
+    for i in 0..10 {
+        assert!((f(i as f64) - 1.0).abs() < 1e-12);
+    }
+    assert_eq!(Point::<()> {x: 2.0, y: 0.0, z: 1.0}.norm2(), 5.0);
+}
@@ -0,0 +1,130 @@
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn hmap_dedup_collapses_runs() {
+    let step: Func<f64, f64> = Arc::new(|t| t.floor());
+    let q: Vec<f64> = hmap_dedup(vec![0.1, 0.5, 1.2, 1.9, 0.3, 2.5, 2.7], &step);
+    assert_eq!(q, vec![0.0, 1.0, 0.0, 2.0]);
+}
+
+#[test]
+fn hmap_grid_jagged_indices() {
+    let f: Func<(usize, usize, f64), f64> =
+        Arc::new(|(i, j, v)| i as f64 * 100.0 + j as f64 * 10.0 + v);
+    let grid = vec![vec![1.0, 2.0, 3.0], vec![], vec![4.0]];
+    let q: Vec<Vec<f64>> = hmap_grid(grid, &f);
+    assert_eq!(q, vec![vec![1.0, 12.0, 23.0], vec![], vec![204.0]]);
+}
+
+#[test]
+fn hmap_accum_running_sum() {
+    let (total, q) = hmap_accum(vec![1.0, 2.0, 3.0, 4.0], 0.0, |s, x| (s + x, (s + x, x * 10.0)));
+    assert_eq!(total, 10.0);
+    assert_eq!(q, vec![(1.0, 10.0), (3.0, 20.0), (6.0, 30.0), (10.0, 40.0)]);
+}
+
+#[test]
+fn hmap_prioritized_keeps_priorities() {
+    let f: Func<f64, f64> = Arc::new(|t| t + 0.5);
+    let q: Vec<(i32, f64)> = hmap_prioritized(vec![(3, 1.0), (-1, 2.0), (7, 3.0)], &f);
+    assert_eq!(q, vec![(3, 1.5), (-1, 2.5), (7, 3.5)]);
+}
+
+#[test]
+fn hmap_flat_concatenates() {
+    let f: Func<f64, f64> = Arc::new(|t| t * 2.0);
+    let q: Vec<f64> = hmap_flat(vec![vec![1.0, 2.0], vec![], vec![3.0]], &f);
+    assert_eq!(q, vec![2.0, 4.0, 6.0]);
+}
+
+#[test]
+fn interleave_equal_and_unequal() {
+    assert_eq!(interleave(vec![1, 3, 5], vec![2, 4, 6]), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(interleave(vec![1, 3, 5, 7, 8], vec![2, 4]), vec![1, 2, 3, 4, 5, 7, 8]);
+    assert_eq!(interleave(vec![1], vec![2, 4, 6]), vec![1, 2, 4, 6]);
+    assert_eq!(interleave::<i32>(vec![], vec![]), vec![]);
+}
+
+#[test]
+fn hmap_soa_lockstep() {
+    let fx: Func<f64, f64> = Arc::new(|t| t + 1.0);
+    let fy: Func<f64, f64> = Arc::new(|t| t * 2.0);
+    let fz: Func<f64, f64> = Arc::new(|t| -t);
+    let q: Option<[Vec<f64>; 3]> =
+        hmap_soa([vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]], [&fx, &fy, &fz]);
+    assert_eq!(q, Some([vec![2.0, 3.0], vec![6.0, 8.0], vec![-5.0, -6.0]]));
+    let q: Option<[Vec<f64>; 3]> =
+        hmap_soa([vec![1.0, 2.0], vec![3.0], vec![5.0, 6.0]], [&fx, &fy, &fz]);
+    assert_eq!(q, None);
+}
+
+#[test]
+fn hmap_iter_is_lazy() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let f: Func<f64, f64> = Arc::new(move |t| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        t * 2.0
+    });
+    let mut it = hmap_iter::<_, f64, f64>(vec![1.0, 2.0, 3.0, 4.0, 5.0], &f);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(it.next(), Some(2.0));
+    assert_eq!(it.next(), Some(4.0));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let slice = [1.0, 2.0];
+    let q: Vec<f64> = hmap_iter(slice.iter().cloned(), &f).collect();
+    assert_eq!(q, vec![2.0, 4.0]);
+}
+
+#[test]
+fn hmap_enumerate_iter_indices() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let f: Func<f64, f64> = Arc::new(move |t| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        t + 1.0
+    });
+    let mut it = hmap_enumerate_iter::<_, f64, f64>(vec![10.0, 20.0, 30.0], &f);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    assert_eq!(it.next(), Some((0, 11.0)));
+    assert_eq!(it.next(), Some((1, 21.0)));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(it.next(), Some((2, 31.0)));
+    assert_eq!(it.next(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hmap_to_lookup_both_outputs() {
+    let f: Func<i32, i32> = Arc::new(|x| x * x);
+    let (out, lookup) = hmap_to_lookup::<i32, i32>(vec![1, -2, 3, 2, 1], &f);
+    assert_eq!(out, vec![1, 4, 9, 4, 1]);
+    assert_eq!(lookup.len(), 4);
+    assert_eq!(lookup[&-2], 4);
+    assert_eq!(lookup[&2], 4);
+    assert_eq!(lookup[&3], 9);
+    assert_eq!(lookup.get(&5), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn hmap_dedup_input_calls_once_per_input() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let f: Func<u8, u32> = Arc::new(move |x| {
+        c.fetch_add(1, Ordering::SeqCst);
+        x as u32 * 3
+    });
+    let data: Vec<u8> = (0..1000).map(|i| (i % 4) as u8).collect();
+    let out = hmap_dedup_input::<u8, u32>(data.clone(), &f);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    assert!(out.iter().zip(&data).all(|(&u, &x)| u == x as u32 * 3));
+}
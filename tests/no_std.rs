@@ -0,0 +1,27 @@
+//! Checks that the crate is usable from a `no_std` crate with `alloc`.
+//!
+//! Run with `cargo test --no-default-features --test no_std`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use ha::*;
+
+#[test]
+fn hmap_vec_without_std() {
+    let f: Func<f64, f64> = Arc::new(|t| t * 2.0 + 1.0);
+    let q: Vec<f64> = vec![0.0, 1.0, 2.5].hmap(&f);
+    assert_eq!(q, vec![1.0, 3.0, 6.0]);
+}
+
+#[test]
+fn hpair_vec_without_std() {
+    let sum: Func<(i32, i32), i32> = Arc::new(|(a, b)| a + b);
+    let q: Vec<i32> = (vec![1, 2, 3], vec![10, 20, 30]).hpair().hmap(&sum);
+    assert_eq!(q, vec![11, 22, 33]);
+    assert!((vec![1], vec![1, 2]).try_hpair().is_err());
+}
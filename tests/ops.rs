@@ -0,0 +1,105 @@
+use ha::*;
+use std::sync::Arc;
+
+#[cfg(feature = "std")]
+#[test]
+fn length_ordinary_and_higher_order() {
+    assert_eq!([3.0, 4.0].length(), 5.0);
+    assert_eq!([2.0, 3.0, 6.0].length(), 7.0);
+    assert_eq!([1.0, 1.0, 1.0, 1.0].length(), 2.0);
+
+    let x: Func<f64, f64> = Arc::new(|t| 3.0 * t);
+    let y: Func<f64, f64> = Arc::new(|t| 4.0 * t);
+    let len = [x, y].length();
+    assert_eq!(len(1.0), 5.0);
+    assert_eq!(len(2.0), 10.0);
+}
+
+#[test]
+fn reflect_about_yz_plane() {
+    let n = [1.0, 0.0, 0.0];
+    assert_eq!([1.0, 0.0, 0.0].reflect_plane(n), [-1.0, 0.0, 0.0]);
+    assert_eq!([1.0, 2.0, 3.0].reflect_plane(n), [-1.0, 2.0, 3.0]);
+
+    let x: Func<f64, f64> = Arc::new(|t| t);
+    let y: Func<f64, f64> = Arc::new(|_| 0.0);
+    let z: Func<f64, f64> = Arc::new(|_| 0.0);
+    let [rx, ry, rz] = [x, y, z].reflect_plane(n);
+    assert_eq!([rx(1.0), ry(1.0), rz(1.0)], [-1.0, 0.0, 0.0]);
+    assert_eq!(rx(2.5), -2.5);
+}
+
+#[test]
+fn hmix_fun_crossfades() {
+    let a: Func<f64, f64> = Arc::new(|_| 2.0);
+    let b: Func<f64, f64> = Arc::new(|_| 4.0);
+    let t: Func<f64, f64> = Arc::new(|t| t);
+    let c = a.hmix_fun(b, t.clone());
+    assert_eq!(c(0.0), 2.0);
+    assert_eq!(c(0.5), 3.0);
+    assert_eq!(c(1.0), 4.0);
+
+    let p: [Func<f64, f64>; 2] = [Arc::new(|_| 0.0), Arc::new(|_| 1.0)];
+    let q: [Func<f64, f64>; 2] = [Arc::new(|_| 1.0), Arc::new(|_| 0.0)];
+    let [x, y] = p.hmix_fun(q, t);
+    assert_eq!([x(0.25), y(0.25)], [0.25, 0.75]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn combine_sphere_sdfs() {
+    let a = sphere_sdf([0.0, 0.0, 0.0], 1.0);
+    let b = sphere_sdf([1.5, 0.0, 0.0], 1.0);
+    let inside_a = [-0.5, 0.0, 0.0];
+    let inside_b = [2.0, 0.0, 0.0];
+    let inside_both = [0.75, 0.0, 0.0];
+    let outside = [0.0, 5.0, 0.0];
+
+    let u = a.clone().union(b.clone());
+    assert!(u(inside_a) < 0.0 && u(inside_b) < 0.0 && u(inside_both) < 0.0);
+    assert!(u(outside) > 0.0);
+
+    let i = a.clone().intersect(b.clone());
+    assert!(i(inside_both) < 0.0);
+    assert!(i(inside_a) > 0.0 && i(inside_b) > 0.0);
+
+    let s = a.clone().subtract(b.clone());
+    assert!(s(inside_a) < 0.0);
+    assert!(s(inside_both) > 0.0 && s(inside_b) > 0.0);
+
+    let su = a.clone().smooth_union(b.clone(), 0.5);
+    assert!(su(inside_both) <= u(inside_both));
+    assert!(su(outside) > 0.0);
+
+    for &k in &[0.0, -1.0] {
+        let hard = a.clone().smooth_union(b.clone(), k);
+        for &p in &[inside_a, inside_both, outside] {
+            assert_eq!(hard(p), u(p));
+        }
+    }
+}
+
+#[test]
+fn hdiff_pointwise() {
+    let a: Func<f64, f64> = Arc::new(|t| t * t);
+    let b: Func<f64, f64> = Arc::new(|t| 2.0 * t);
+    let d = a.clone().hdiff(b.clone());
+    for i in 0..10 {
+        let t = i as f64 * 0.3;
+        assert_eq!(d(t), a(t) - b(t));
+    }
+    assert_eq!([3.0, 5.0].hdiff([1.0, 1.0]), [2.0, 4.0]);
+}
+
+#[test]
+fn hderivative_of_square() {
+    let f: Func<f64, f64> = Arc::new(|t| t * t);
+    let d = f.hderivative(1e-4);
+    for &t in &[-2.0, 0.0, 0.5, 3.0] {
+        assert!((d(t) - 2.0 * t).abs() < 1e-8);
+    }
+    let fs: [Func<f64, f64>; 2] = [Arc::new(|t| 3.0 * t), Arc::new(|t| t * t * t)];
+    let [a, b] = fs.hderivative(1e-4);
+    assert!((a(1.0) - 3.0).abs() < 1e-8);
+    assert!((b(2.0) - 12.0).abs() < 1e-6);
+}
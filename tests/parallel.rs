@@ -0,0 +1,45 @@
+#![cfg(feature = "parallel")]
+
+use ha::*;
+use std::sync::Arc;
+
+#[test]
+fn hmap_pooled_matches_sequential() {
+    let f: Func<f64, f64> = Arc::new(|t| (t * 0.001).sin() * t);
+    let data: Vec<f64> = (0..100_000).map(|i| i as f64).collect();
+    let seq: Vec<f64> = data.clone().hmap(&f);
+    for &threads in &[1, 3, 8] {
+        let par: Vec<f64> = data.clone().hmap_pooled(&f, threads);
+        assert_eq!(par, seq);
+    }
+    let empty: Vec<f64> = Vec::<f64>::new().hmap_pooled(&f, 4);
+    assert!(empty.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn hmap_pooled_propagates_panic() {
+    let f: Func<f64, f64> = Arc::new(|t| if t == 500.0 {panic!("bad sample")} else {t});
+    let data: Vec<f64> = (0..1000).map(|i| i as f64).collect();
+    let _: Vec<f64> = data.hmap_pooled(&f, 4);
+}
+
+#[test]
+fn par_hmap_chunked_matches_sequential() {
+    let f: Func<f64, f64> = Arc::new(|t| t * t - 1.0);
+    let data: Vec<f64> = (0..10_000).map(|i| i as f64).collect();
+    let seq: Vec<f64> = data.clone().hmap(&f);
+    for &chunk_size in &[0, 1, 7, 1000, 20_000] {
+        let par: Vec<f64> = data.clone().par_hmap_chunked(&f, chunk_size);
+        assert_eq!(par, seq);
+    }
+}
+
+#[test]
+fn par_hmap_matches_sequential() {
+    let f: Func<f64, f64> = Arc::new(|t| (t * 0.01).cos() + t);
+    let data: Vec<f64> = (0..10_000).map(|i| i as f64).collect();
+    let seq: Vec<f64> = data.clone().hmap(&f);
+    let par: Vec<f64> = data.par_hmap(&f);
+    assert_eq!(par, seq);
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "simd")]
+#![feature(portable_simd)]
+
+use ha::*;
+use std::simd::{f32x4, num::SimdFloat};
+use std::sync::Arc;
+
+#[test]
+fn call_simd_function() {
+    let f: Func<f32, f32x4> = Arc::new(|t| f32x4::splat(t) * f32x4::from_array([1.0, 2.0, 3.0, 4.0]));
+    let v = <f32x4 as Call<f32>>::call(&f, 2.0);
+    assert_eq!(v.to_array(), [2.0, 4.0, 6.0, 8.0]);
+    assert_eq!(v.reduce_sum(), 20.0);
+    let q: [f32x4; 2] = [1.0, 2.0].hmap(&f);
+    assert_eq!(q[1], v);
+}
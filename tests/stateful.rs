@@ -0,0 +1,109 @@
+use ha::*;
+
+#[cfg(feature = "std")]
+#[test]
+fn hysteresis_latches() {
+    let f = hysteresis(0.2, 0.8);
+    let input = [0.0, 0.5, 0.9, 0.5, 0.3, 0.1, 0.5, 0.81];
+    let out: Vec<f64> = input.iter().map(|&t| f(t)).collect();
+    assert_eq!(out, vec![0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn call_limit_returns_sentinel() {
+    let f: Func<f64, f64> = std::sync::Arc::new(|t| t * 2.0);
+    let g = call_limit(f, 3, -1.0);
+    let out: Vec<f64> = (1..=5).map(|i| g(i as f64)).collect();
+    assert_eq!(out, vec![2.0, 4.0, 6.0, -1.0, -1.0]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn running_stats_welford() {
+    let f = running_stats();
+    assert_eq!(f(2.0), (2.0, 0.0));
+    let mut last = (0.0, 0.0);
+    for &x in &[4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+        last = f(x);
+    }
+    assert!((last.0 - 5.0).abs() < 1e-12);
+    assert!((last.1 - 4.0).abs() < 1e-12);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn memoize_lru_evicts_least_recent() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let f: Func<u32, u32> = Arc::new(move |t| {
+        c.fetch_add(1, Ordering::SeqCst);
+        t * 10
+    });
+    let g = memoize_lru(f, 2);
+    assert_eq!(g(1), 10);
+    assert_eq!(g(2), 20);
+    assert_eq!(g(1), 10);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    // Evicts `2`, which was used least recently.
+    assert_eq!(g(3), 30);
+    assert_eq!(g(1), 10);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(g(2), 20);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    // Only `2` and `1` remain cached after `3` was evicted.
+    assert_eq!(g(1), 10);
+    assert_eq!(g(3), 30);
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn memoize_lru_evicts_by_use_not_insertion() {
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(vec![]));
+    let l = log.clone();
+    let f: Func<u32, u32> = Arc::new(move |t| {
+        l.lock().unwrap().push(t);
+        t + 1
+    });
+    let g = memoize_lru(f, 3);
+    for &t in &[1, 2, 3] {g(t);}
+    // Use the oldest inserted entries, such that `3` is least recently used.
+    g(1);
+    g(2);
+    g(4);
+    log.lock().unwrap().clear();
+    for &t in &[1, 2, 4, 3] {g(t);}
+    assert_eq!(*log.lock().unwrap(), vec![3]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_change_emits_changes() {
+    let f: Func<i32, i32> = std::sync::Arc::new(|t| t / 10);
+    let g = on_change(f);
+    let out: Vec<Option<i32>> = [3, 3, 5, 12, 15, 4].iter().map(|&t| g(t)).collect();
+    assert_eq!(out, vec![Some(0), None, None, Some(1), None, Some(0)]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn memoize_calls_once_per_input() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let f: Func<i32, i32> = Arc::new(move |t| {
+        c.fetch_add(1, Ordering::SeqCst);
+        t + 100
+    });
+    let g = memoize(f);
+    let out: Vec<i32> = [1, 2, 1, 3, 2, 1].iter().map(|&t| g(t)).collect();
+    assert_eq!(out, vec![101, 102, 101, 103, 102, 101]);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}